@@ -0,0 +1,12 @@
+use std::process::Command;
+
+// exposes the commit hash to get_version, builds outside of a git checkout report "unknown"
+fn main() {
+    if let Ok(output) = Command::new("git").args(["rev-parse", "--short", "HEAD"]).output() {
+        if output.status.success() {
+            println!("cargo:rustc-env=GIT_HASH={}", String::from_utf8_lossy(&output.stdout).trim());
+        }
+    }
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+}
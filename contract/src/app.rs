@@ -1,10 +1,37 @@
+use near_sdk::BlockHeight;
 use crate::*;
 
+pub const SLUG_TOMBSTONE_PERIOD: u64 = 30 * 24 * 60 * 60 * 1_000_000_000; // 30 days
+
 #[derive(BorshSerialize, BorshDeserialize)]
 pub enum VApp {
+    V1(AppV1),
     Current(App),
 }
 
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct AppV1 {
+    pub added_by_account_id: AccountId,
+    pub dapp_account_id: AccountId,
+
+    pub slug: Slug,
+    pub title: String,
+    pub categories: UnorderedSet<CategoryId>,
+    pub oneliner: Option<String>,
+    pub description: Option<String>,
+    pub logo_url: Option<String>,
+    pub twitter: Option<String>,
+    pub facebook: Option<String>,
+    pub medium: Option<String>,
+    pub telegram: Option<String>,
+    pub github: Option<String>,
+    pub discord: Option<String>,
+    pub symbol: Option<String>,
+    pub contracts: UnorderedSet<AccountId>,
+    pub token_address: Option<AccountId>,
+    pub active: Option<bool>,
+}
+
 
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct App {
@@ -27,11 +54,36 @@ pub struct App {
     pub contracts: UnorderedSet<AccountId>,
     pub token_address: Option<AccountId>,
     pub active: Option<bool>,
+    pub web4_check: Option<Web4Check>,
+    // block of the last recheck_app request, its probe may still be in flight
+    pub web4_check_requested_block_height: Option<BlockHeight>,
 }
 
 impl From<VApp> for App {
     fn from(v_app: VApp) -> Self {
         match v_app {
+            VApp::V1(app) => App {
+                added_by_account_id: app.added_by_account_id,
+                dapp_account_id: app.dapp_account_id,
+                slug: app.slug,
+                title: app.title,
+                categories: app.categories,
+                oneliner: app.oneliner,
+                description: app.description,
+                logo_url: app.logo_url,
+                twitter: app.twitter,
+                facebook: app.facebook,
+                medium: app.medium,
+                telegram: app.telegram,
+                github: app.github,
+                discord: app.discord,
+                symbol: app.symbol,
+                contracts: app.contracts,
+                token_address: app.token_address,
+                active: app.active,
+                web4_check: None,
+                web4_check_requested_block_height: None,
+            },
             VApp::Current(app) => app,
         }
     }
@@ -63,45 +115,61 @@ pub struct AppJSON {
 
 impl From<VApp> for AppJSON {
     fn from(v_app: VApp) -> Self {
-        match v_app {
-            VApp::Current(app) => {
-                let mut categories = vec![];
-                for category in app.categories.to_vec() {
-                    categories.push(category.to_string())
-                }
-
-                AppJSON {
-                    added_by_account_id: Some(app.added_by_account_id),
-                    dapp_account_id: app.dapp_account_id,
-                    slug: app.slug,
-                    title: app.title,
-                    categories,
-                    oneliner: app.oneliner,
-                    description: app.description,
-                    logo_url: app.logo_url,
-                    twitter: app.twitter,
-                    facebook: app.facebook,
-                    medium: app.medium,
-                    telegram: app.telegram,
-                    github: app.github,
-                    discord: app.discord,
-                    symbol: app.symbol,
-                    contracts: Some(app.contracts.to_vec()),
-                    token_address: app.token_address,
-                    active: app.active,
-                }
-            }
+        let app: App = v_app.into();
+        let mut categories = vec![];
+        for category in app.categories.to_vec() {
+            categories.push(category.to_string())
+        }
+
+        AppJSON {
+            added_by_account_id: Some(app.added_by_account_id),
+            dapp_account_id: app.dapp_account_id,
+            slug: app.slug,
+            title: app.title,
+            categories,
+            oneliner: app.oneliner,
+            description: app.description,
+            logo_url: app.logo_url,
+            twitter: app.twitter,
+            facebook: app.facebook,
+            medium: app.medium,
+            telegram: app.telegram,
+            github: app.github,
+            discord: app.discord,
+            symbol: app.symbol,
+            contracts: Some(app.contracts.to_vec()),
+            token_address: app.token_address,
+            active: app.active,
         }
     }
 }
 
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SlugTombstone {
+    pub account_id: AccountId,
+    pub expires_at: u64,
+}
+
 impl Contract {
     pub(crate) fn internal_insert_app(&mut self, app_id: AppId, app: AppJSON, added_by_account_id: Option<AccountId>,
                                       categories: UnorderedSet<CategoryId>, contracts: UnorderedSet<AccountId>) {
         let slug = filter_slug(app.slug).to_lowercase();
+        self.slug_tombstones.remove(&slug);
         self.app_id_by_slug.insert(&slug, &app_id);
+        if let Some(added_by_account_id) = added_by_account_id.as_ref() {
+            self.internal_add_app_to_author(app_id, added_by_account_id);
+        }
         self.app_id_by_dapp_account_id.insert(&app.dapp_account_id, &app_id);
 
+        // dapp account can't be changed, so liveness checks survive listing updates
+        let (web4_check, web4_check_requested_block_height) = self.apps.get(&app_id)
+            .map(|v_app| {
+                let app = App::from(v_app);
+                (app.web4_check, app.web4_check_requested_block_height)
+            })
+            .unwrap_or_default();
+
         let app = App {
             added_by_account_id: added_by_account_id.expect("ERR_MISSING_ADDED_BY_ACCOUNT_ID"),
             dapp_account_id: app.dapp_account_id,
@@ -121,8 +189,174 @@ impl Contract {
             contracts,
             token_address: app.token_address,
             active: app.active,
+            web4_check,
+            web4_check_requested_block_height,
         };
 
+        if app.active.unwrap_or(true) {
+            self.internal_add_app_to_categories(app_id, &app);
+            self.internal_add_app_to_search_index(app_id, &app);
+            self.active_app_ids.insert(&app_id);
+            self.disabled_app_ids.remove(&app_id);
+        } else {
+            self.active_app_ids.remove(&app_id);
+            self.disabled_app_ids.insert(&app_id);
+        }
+
         self.apps.insert(&app_id, &VApp::Current(app));
     }
+
+    pub(crate) fn internal_add_app(&mut self, app_id: AppId, mut app: AppJSON, added_by_account_id: AccountId) {
+        let mut categories = UnorderedSet::new(StorageKey::AppCategories { app_id });
+        for category_string in app.categories.clone() {
+            let category_id: CategoryId = category_string.parse().expect("ERR_WRONG_CATEGORY");
+            if self.categories.get(&category_id).is_some() {
+                categories.insert(&category_id);
+            }
+        }
+        let mut contracts = UnorderedSet::new(StorageKey::AppContracts { app_id });
+        let contracts_vec = app.contracts.clone().unwrap_or_default();
+        for contract in contracts_vec {
+            contracts.insert(&contract);
+        }
+        app.active = Some(true);
+        self.internal_insert_app(app_id, app, Some(added_by_account_id), categories, contracts);
+        self.internal_increase_active_apps(app_id);
+
+        let new_app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+        // listing submitted by the dapp account itself needs no further proof
+        if new_app.added_by_account_id == new_app.dapp_account_id {
+            self.internal_set_app_owner_verified(app_id, &new_app.added_by_account_id);
+        }
+        Event::AppAdded {
+            app_id,
+            slug: &new_app.slug,
+            dapp_account_id: &new_app.dapp_account_id,
+            added_by_account_id: &new_app.added_by_account_id,
+        }.emit();
+    }
+
+    pub(crate) fn internal_update_app(&mut self, app_id: AppId, app: AppJSON, old_app: App) {
+        self.assert_slug_available(&filter_slug(app.slug.clone()).to_lowercase(), &old_app.added_by_account_id, Some(app_id));
+
+        // counter changes only when the app switches between active and disabled
+        let was_active = old_app.active.unwrap_or(true);
+        let is_active = app.active.unwrap_or(true);
+        if was_active && !is_active {
+            self.internal_decrease_active_apps(app_id);
+        } else if !was_active && is_active {
+            self.internal_increase_active_apps(app_id);
+        }
+
+        /* REMOVE OLD DATA */
+        self.app_id_by_slug.remove(&old_app.slug);
+        self.app_id_by_dapp_account_id.remove(&old_app.dapp_account_id);
+        self.internal_remove_app_from_search_index(app_id, &old_app);
+        self.internal_remove_app_from_categories(app_id, &old_app);
+
+        /* CREATE NEW DATA */
+        let mut categories = old_app.categories;
+        categories.clear();
+        for category_string in app.categories.clone() {
+            let category_id: CategoryId = category_string.parse().expect("ERR_WRONG_CATEGORY");
+            if self.categories.get(&category_id).is_some() {
+                categories.insert(&category_id);
+            }
+        }
+        let mut contracts = old_app.contracts;
+        let old_contracts = contracts.to_vec();
+        contracts.clear();
+        let contracts_vec = app.contracts.clone().unwrap_or_default();
+        for contract in contracts_vec {
+            contracts.insert(&contract);
+        }
+        for contract_id in old_contracts {
+            if !contracts.contains(&contract_id) && self.is_app_contract_verified(app_id, &contract_id) {
+                self.app_id_by_verified_contract.remove(&contract_id);
+            }
+        }
+
+        // ownership is changed only by transfer_app_ownership
+        let author_id = old_app.added_by_account_id;
+        self.internal_insert_app(app_id, app, Some(author_id.clone()), categories, contracts);
+
+        let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+        // a renamed app keeps its old slug reserved for the author like a deleted one
+        if app.slug != old_app.slug {
+            self.internal_add_slug_tombstone(&old_app.slug, author_id);
+        }
+        Event::AppUpdated { app_id, slug: &app.slug, updated_by_account_id: &env::predecessor_account_id() }.emit();
+    }
+
+    // clears every index of the app and keeps its slug reserved for the author during the cooling-off period
+    pub(crate) fn internal_remove_app(&mut self, app_id: AppId, mut app: App) {
+        self.app_id_by_slug.remove(&app.slug);
+        self.app_id_by_dapp_account_id.remove(&app.dapp_account_id);
+        self.internal_remove_app_from_search_index(app_id, &app);
+        self.internal_remove_app_from_categories(app_id, &app);
+        self.active_app_ids.remove(&app_id);
+        self.disabled_app_ids.remove(&app_id);
+        self.internal_remove_app_from_author(app_id, &app.added_by_account_id);
+        self.internal_remove_app_contract_verifications(app_id, &app);
+        self.verified_app_owners.remove(&app_id);
+        self.web4_metadata_by_app_id.remove(&app_id);
+
+        app.categories.clear();
+        app.contracts.clear();
+        self.apps.remove(&app_id);
+
+        self.internal_add_slug_tombstone(&app.slug, app.added_by_account_id);
+    }
+
+    pub(crate) fn internal_add_slug_tombstone(&mut self, slug: &Slug, account_id: AccountId) {
+        self.slug_tombstones.insert(slug, &SlugTombstone {
+            account_id,
+            expires_at: env::block_timestamp() + SLUG_TOMBSTONE_PERIOD,
+        });
+    }
+
+    pub(crate) fn assert_slug_available(&self, slug: &Slug, account_id: &AccountId, app_id: Option<AppId>) {
+        if let Some(slug_app_id) = self.app_id_by_slug.get(slug) {
+            require!(Some(slug_app_id) == app_id, "ERR_SLUG_ALREADY_EXISTS");
+        }
+        if let Some(tombstone) = self.slug_tombstones.get(slug) {
+            require!(&tombstone.account_id == account_id || tombstone.expires_at <= env::block_timestamp(), "ERR_SLUG_IS_RESERVED");
+        }
+    }
+
+    pub(crate) fn internal_add_app_to_author(&mut self, app_id: AppId, account_id: &AccountId) {
+        let mut app_ids = self.app_ids_by_author.get(account_id).unwrap_or_else(||
+            UnorderedSet::new(StorageKey::AppIdsSetByAuthor { account_id: account_id.clone() }));
+        app_ids.insert(&app_id);
+        self.app_ids_by_author.insert(account_id, &app_ids);
+    }
+
+    pub(crate) fn internal_remove_app_from_author(&mut self, app_id: AppId, account_id: &AccountId) {
+        if let Some(mut app_ids) = self.app_ids_by_author.get(account_id) {
+            app_ids.remove(&app_id);
+            if app_ids.is_empty() {
+                self.app_ids_by_author.remove(account_id);
+            } else {
+                self.app_ids_by_author.insert(account_id, &app_ids);
+            }
+        }
+    }
+
+    pub(crate) fn internal_add_app_to_categories(&mut self, app_id: AppId, app: &App) {
+        for category_id in app.categories.iter() {
+            if let Some(mut apps_ids_by_category_id) = self.apps_ids_by_category_id.get(&category_id) {
+                apps_ids_by_category_id.insert(&app_id);
+                self.apps_ids_by_category_id.insert(&category_id, &apps_ids_by_category_id);
+            }
+        }
+    }
+
+    pub(crate) fn internal_remove_app_from_categories(&mut self, app_id: AppId, app: &App) {
+        for category_id in app.categories.iter() {
+            if let Some(mut apps_ids_by_category_id) = self.apps_ids_by_category_id.get(&category_id) {
+                apps_ids_by_category_id.remove(&app_id);
+                self.apps_ids_by_category_id.insert(&category_id, &apps_ids_by_category_id);
+            }
+        }
+    }
 }
\ No newline at end of file
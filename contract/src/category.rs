@@ -2,19 +2,51 @@ use crate::*;
 
 #[derive(BorshSerialize, BorshDeserialize)]
 pub enum VCategory {
+    V1(CategoryV1),
+    V2(CategoryV2),
     Current(Category),
 }
 
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct CategoryV1 {
+    pub slug: String,
+    pub title: String
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct CategoryV2 {
+    pub slug: String,
+    pub title: String,
+    pub order: u64,
+    pub hidden: bool
+}
 
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct Category {
     pub slug: String,
-    pub title: String
+    pub title: String,
+    pub order: u64,
+    pub hidden: bool,
+    pub parent_id: Option<CategoryId>
 }
 
 impl From<VCategory> for Category {
     fn from(v_category: VCategory) -> Self {
         match v_category {
+            VCategory::V1(category) => Category {
+                slug: category.slug,
+                title: category.title,
+                order: 0,
+                hidden: false,
+                parent_id: None
+            },
+            VCategory::V2(category) => Category {
+                slug: category.slug,
+                title: category.title,
+                order: category.order,
+                hidden: category.hidden,
+                parent_id: None
+            },
             VCategory::Current(category) => category,
         }
     }
@@ -24,16 +56,119 @@ impl From<VCategory> for Category {
 #[serde(crate = "near_sdk::serde")]
 pub struct CategoryJSON {
     pub slug: String,
-    pub title: String
+    pub title: String,
+    pub order: u64,
+    pub hidden: bool,
+    pub parent_id: Option<CategoryId>
 }
 
 impl From<VCategory> for CategoryJSON {
     fn from(v_category: VCategory) -> Self {
-        match v_category {
-            VCategory::Current(category) => CategoryJSON {
-                slug: category.slug,
-                title: category.title
+        let category: Category = v_category.into();
+        CategoryJSON {
+            slug: category.slug,
+            title: category.title,
+            order: category.order,
+            hidden: category.hidden,
+            parent_id: category.parent_id
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CategoryTreeJSON {
+    pub category_id: CategoryId,
+    pub slug: String,
+    pub title: String,
+    pub hidden: bool,
+    pub children: Vec<CategoryTreeJSON>
+}
+
+impl Contract {
+    // categories sorted for menus and forms, equal order keeps creation order
+    pub(crate) fn internal_get_sorted_categories(&self, include_hidden: bool) -> Vec<(CategoryId, Category)> {
+        let mut categories: Vec<(CategoryId, Category)> = self.categories.iter()
+            .map(|(category_id, v_category)| (category_id, v_category.into()))
+            .filter(|(_, category): &(CategoryId, Category)| include_hidden || !category.hidden)
+            .collect();
+        categories.sort_by_key(|(category_id, category)| (category.order, *category_id));
+        categories
+    }
+
+    // sorted categories grouped by parent, categories with a missing parent become top level
+    pub(crate) fn internal_get_categories_by_parent(&self, include_hidden: bool) -> HashMap<Option<CategoryId>, Vec<(CategoryId, Category)>> {
+        let categories = self.internal_get_sorted_categories(include_hidden);
+        let category_ids: Vec<CategoryId> = categories.iter().map(|(category_id, _)| *category_id).collect();
+        let mut categories_by_parent: HashMap<Option<CategoryId>, Vec<(CategoryId, Category)>> = HashMap::new();
+        for (category_id, category) in categories {
+            let parent_id = category.parent_id.filter(|parent_id| category_ids.contains(parent_id));
+            categories_by_parent.entry(parent_id).or_default().push((category_id, category));
+        }
+        categories_by_parent
+    }
+
+    pub(crate) fn internal_get_descendant_category_ids(&self, category_id: CategoryId) -> Vec<CategoryId> {
+        let mut children_by_parent: HashMap<CategoryId, Vec<CategoryId>> = HashMap::new();
+        for (child_id, v_category) in self.categories.iter() {
+            let category: Category = v_category.into();
+            if let Some(parent_id) = category.parent_id {
+                children_by_parent.entry(parent_id).or_default().push(child_id);
             }
         }
+
+        let mut category_ids = vec![category_id];
+        let mut index = 0;
+        while index < category_ids.len() {
+            if let Some(children) = children_by_parent.get(&category_ids[index]) {
+                category_ids.extend(children);
+            }
+            index += 1;
+        }
+        category_ids
     }
-}
\ No newline at end of file
+
+    // ids of active apps in the category and all its descendants, sorted and deduplicated
+    pub(crate) fn internal_get_category_tree_app_ids(&self, category_id: CategoryId) -> Vec<AppId> {
+        let mut app_ids = vec![];
+        for category_id in self.internal_get_descendant_category_ids(category_id) {
+            if let Some(category_app_ids) = self.apps_ids_by_category_id.get(&category_id) {
+                app_ids.extend(category_app_ids.iter());
+            }
+        }
+        app_ids.sort_unstable();
+        app_ids.dedup();
+        app_ids
+    }
+
+    pub(crate) fn internal_get_category_tree_apps_count(&self, category_id: CategoryId) -> u64 {
+        let category_ids = self.internal_get_descendant_category_ids(category_id);
+        if category_ids.len() == 1 {
+            self.apps_ids_by_category_id.get(&category_id).expect("ERR_NO_CATEGORY").len()
+        } else {
+            self.internal_get_category_tree_app_ids(category_id).len() as u64
+        }
+    }
+
+    pub(crate) fn assert_category_parent(&self, category_id: Option<CategoryId>, parent_id: Option<CategoryId>) {
+        let mut ancestor_id = parent_id;
+        while let Some(current_id) = ancestor_id {
+            require!(Some(current_id) != category_id, "ERR_CATEGORY_CYCLE");
+            let ancestor: Category = self.categories.get(&current_id).expect("ERR_NO_PARENT_CATEGORY").into();
+            ancestor_id = ancestor.parent_id;
+        }
+    }
+}
+
+pub(crate) fn build_category_tree(categories_by_parent: &mut HashMap<Option<CategoryId>, Vec<(CategoryId, Category)>>, parent_id: Option<CategoryId>) -> Vec<CategoryTreeJSON> {
+    categories_by_parent.remove(&parent_id).unwrap_or_default()
+        .into_iter()
+        .map(|(category_id, category)| CategoryTreeJSON {
+            category_id,
+            slug: category.slug,
+            title: category.title,
+            hidden: category.hidden,
+            children: build_category_tree(categories_by_parent, Some(category_id))
+        })
+        .collect()
+}
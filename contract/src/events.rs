@@ -0,0 +1,232 @@
+use crate::*;
+use near_sdk::json_types::Base58CryptoHash;
+
+pub const EVENT_STANDARD: &str = "awesomeweb4";
+pub const EVENT_STANDARD_VERSION: &str = "1.0.0";
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum Event<'a> {
+    AppAdded { app_id: AppId, slug: &'a str, dapp_account_id: &'a AccountId, added_by_account_id: &'a AccountId },
+    ListingRejected { dapp_account_id: &'a AccountId, added_by_account_id: &'a AccountId, reason: &'a str },
+    AppUpdated { app_id: AppId, slug: &'a str, updated_by_account_id: &'a AccountId },
+    AppDisabled { app_id: AppId, disabled_by_account_id: &'a AccountId },
+    AppEnabled { app_id: AppId, enabled_by_account_id: &'a AccountId },
+    AppDeleted { app_id: AppId, slug: &'a str, deleted_by_account_id: &'a AccountId },
+    AppOwnershipTransferStarted { app_id: AppId, owner_id: &'a AccountId, new_owner_id: &'a AccountId },
+    AppOwnershipTransferred { app_id: AppId, old_owner_id: &'a AccountId, new_owner_id: &'a AccountId },
+    AppMaintainerAdded { app_id: AppId, account_id: &'a AccountId },
+    AppMaintainerRemoved { app_id: AppId, account_id: &'a AccountId },
+    AppSubmitted { app_id: AppId, submitted_by_account_id: &'a AccountId, is_update: bool },
+    AppApproved { app_id: AppId, approved_by_account_id: &'a AccountId },
+    AppRejected { app_id: AppId, submitted_by_account_id: &'a AccountId, rejected_by_account_id: &'a AccountId, reason: &'a str },
+    CategoryAdded { category_id: CategoryId, slug: &'a str, title: &'a str, parent_id: Option<CategoryId> },
+    CategoryUpdated { category_id: CategoryId, slug: &'a str, title: &'a str, order: u64, hidden: bool, parent_id: Option<CategoryId> },
+    CategoryRemoved { category_id: CategoryId, slug: &'a str },
+    GuardianAdded { account_id: &'a AccountId, added_by_account_id: &'a AccountId },
+    GuardianRemoved { account_id: &'a AccountId, removed_by_account_id: &'a AccountId },
+    AppsRecounted { active_apps: u64 },
+    FeesUpdated { listing_fee: WrappedBalance, update_fee: WrappedBalance },
+    FeesWithdrawn { receiver_id: &'a AccountId, amount: WrappedBalance, withdrawn_by_account_id: &'a AccountId },
+    ProposalAdded { proposal_id: ProposalId, proposer_id: &'a AccountId },
+    ProposalVoted { proposal_id: ProposalId, account_id: &'a AccountId, approve: bool },
+    ProposalExecuted { proposal_id: ProposalId },
+    ProposalRejected { proposal_id: ProposalId },
+    GovernanceUpdated { approval_threshold: Option<u64>, proposal_period: u64 },
+    RoleGranted { account_id: &'a AccountId, role: Role, granted_by_account_id: &'a AccountId },
+    RoleRevoked { account_id: &'a AccountId, role: Role, revoked_by_account_id: &'a AccountId },
+    AppContractVerified { app_id: AppId, contract_id: &'a AccountId },
+    AppContractVerificationRevoked { app_id: AppId, contract_id: &'a AccountId },
+    AppOwnerVerified { app_id: AppId, owner_id: &'a AccountId },
+    AppChecked { app_id: AppId, success: bool, failed_checks: u32, broken: bool },
+    ContractUpgraded { code_hash: Base58CryptoHash, upgraded_by_account_id: &'a AccountId },
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct EventLog<'a> {
+    standard: &'static str,
+    version: &'static str,
+    #[serde(flatten)]
+    event: &'a Event<'a>,
+}
+
+impl Event<'_> {
+    pub fn emit(&self) {
+        let event_log = EventLog {
+            standard: EVENT_STANDARD,
+            version: EVENT_STANDARD_VERSION,
+            event: self,
+        };
+        log!("EVENT_JSON:{}", near_sdk::serde_json::to_string(&event_log).expect("ERR_SERIALIZE_EVENT"));
+    }
+
+    pub(crate) fn category_updated(category_id: CategoryId, category: &Category) {
+        Event::CategoryUpdated {
+            category_id,
+            slug: &category.slug,
+            title: &category.title,
+            order: category.order,
+            hidden: category.hidden,
+            parent_id: category.parent_id,
+        }.emit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::test_utils::get_logs;
+    use crate::test_utils::*;
+    use super::*;
+
+    fn event_log(name: &str, data: &str) -> String {
+        format!(r#"EVENT_JSON:{{"standard":"awesomeweb4","version":"1.0.0","event":"{}","data":{}}}"#, name, data)
+    }
+
+    fn assert_event(event: Event, name: &str, data: &str) {
+        set_predecessor(&guardian());
+        event.emit();
+        assert_eq!(get_logs(), vec![event_log(name, data)]);
+    }
+
+    #[test]
+    fn emits_app_events() {
+        let (alice, bob) = (guardian(), author());
+        let dapp: AccountId = "dapp.near".parse().unwrap();
+        assert_event(Event::AppAdded { app_id: 1, slug: "dapp", dapp_account_id: &dapp, added_by_account_id: &bob },
+                     "app_added", r#"{"app_id":1,"slug":"dapp","dapp_account_id":"dapp.near","added_by_account_id":"bob"}"#);
+        assert_event(Event::ListingRejected { dapp_account_id: &dapp, added_by_account_id: &bob, reason: "ERR_WEB4_GET" },
+                     "listing_rejected", r#"{"dapp_account_id":"dapp.near","added_by_account_id":"bob","reason":"ERR_WEB4_GET"}"#);
+        assert_event(Event::AppUpdated { app_id: 1, slug: "dapp", updated_by_account_id: &bob },
+                     "app_updated", r#"{"app_id":1,"slug":"dapp","updated_by_account_id":"bob"}"#);
+        assert_event(Event::AppDisabled { app_id: 1, disabled_by_account_id: &alice },
+                     "app_disabled", r#"{"app_id":1,"disabled_by_account_id":"alice"}"#);
+        assert_event(Event::AppEnabled { app_id: 1, enabled_by_account_id: &alice },
+                     "app_enabled", r#"{"app_id":1,"enabled_by_account_id":"alice"}"#);
+        assert_event(Event::AppDeleted { app_id: 1, slug: "dapp", deleted_by_account_id: &alice },
+                     "app_deleted", r#"{"app_id":1,"slug":"dapp","deleted_by_account_id":"alice"}"#);
+        assert_event(Event::AppOwnershipTransferStarted { app_id: 1, owner_id: &bob, new_owner_id: &alice },
+                     "app_ownership_transfer_started", r#"{"app_id":1,"owner_id":"bob","new_owner_id":"alice"}"#);
+        assert_event(Event::AppOwnershipTransferred { app_id: 1, old_owner_id: &bob, new_owner_id: &alice },
+                     "app_ownership_transferred", r#"{"app_id":1,"old_owner_id":"bob","new_owner_id":"alice"}"#);
+        assert_event(Event::AppMaintainerAdded { app_id: 1, account_id: &alice },
+                     "app_maintainer_added", r#"{"app_id":1,"account_id":"alice"}"#);
+        assert_event(Event::AppMaintainerRemoved { app_id: 1, account_id: &alice },
+                     "app_maintainer_removed", r#"{"app_id":1,"account_id":"alice"}"#);
+        assert_event(Event::AppSubmitted { app_id: 1, submitted_by_account_id: &bob, is_update: true },
+                     "app_submitted", r#"{"app_id":1,"submitted_by_account_id":"bob","is_update":true}"#);
+        assert_event(Event::AppApproved { app_id: 1, approved_by_account_id: &alice },
+                     "app_approved", r#"{"app_id":1,"approved_by_account_id":"alice"}"#);
+        assert_event(Event::AppRejected { app_id: 1, submitted_by_account_id: &bob, rejected_by_account_id: &alice, reason: "spam" },
+                     "app_rejected", r#"{"app_id":1,"submitted_by_account_id":"bob","rejected_by_account_id":"alice","reason":"spam"}"#);
+        assert_event(Event::AppContractVerified { app_id: 1, contract_id: &dapp },
+                     "app_contract_verified", r#"{"app_id":1,"contract_id":"dapp.near"}"#);
+        assert_event(Event::AppContractVerificationRevoked { app_id: 1, contract_id: &dapp },
+                     "app_contract_verification_revoked", r#"{"app_id":1,"contract_id":"dapp.near"}"#);
+        assert_event(Event::AppOwnerVerified { app_id: 1, owner_id: &bob },
+                     "app_owner_verified", r#"{"app_id":1,"owner_id":"bob"}"#);
+        assert_event(Event::AppChecked { app_id: 1, success: false, failed_checks: 2, broken: false },
+                     "app_checked", r#"{"app_id":1,"success":false,"failed_checks":2,"broken":false}"#);
+        assert_event(Event::AppsRecounted { active_apps: 10 },
+                     "apps_recounted", r#"{"active_apps":10}"#);
+    }
+
+    #[test]
+    fn emits_category_events() {
+        assert_event(Event::CategoryAdded { category_id: 2, slug: "games", title: "Games", parent_id: None },
+                     "category_added", r#"{"category_id":2,"slug":"games","title":"Games","parent_id":null}"#);
+        assert_event(Event::CategoryUpdated { category_id: 2, slug: "games", title: "Games", order: 3, hidden: true, parent_id: Some(1) },
+                     "category_updated", r#"{"category_id":2,"slug":"games","title":"Games","order":3,"hidden":true,"parent_id":1}"#);
+        assert_event(Event::CategoryRemoved { category_id: 2, slug: "games" },
+                     "category_removed", r#"{"category_id":2,"slug":"games"}"#);
+    }
+
+    #[test]
+    fn emits_governance_events() {
+        let (alice, bob) = (guardian(), author());
+        assert_event(Event::GuardianAdded { account_id: &bob, added_by_account_id: &alice },
+                     "guardian_added", r#"{"account_id":"bob","added_by_account_id":"alice"}"#);
+        assert_event(Event::GuardianRemoved { account_id: &bob, removed_by_account_id: &alice },
+                     "guardian_removed", r#"{"account_id":"bob","removed_by_account_id":"alice"}"#);
+        assert_event(Event::FeesUpdated { listing_fee: WrappedBalance::from(10), update_fee: WrappedBalance::from(5) },
+                     "fees_updated", r#"{"listing_fee":"10","update_fee":"5"}"#);
+        assert_event(Event::FeesWithdrawn { receiver_id: &bob, amount: WrappedBalance::from(7), withdrawn_by_account_id: &alice },
+                     "fees_withdrawn", r#"{"receiver_id":"bob","amount":"7","withdrawn_by_account_id":"alice"}"#);
+        assert_event(Event::ProposalAdded { proposal_id: 4, proposer_id: &alice },
+                     "proposal_added", r#"{"proposal_id":4,"proposer_id":"alice"}"#);
+        assert_event(Event::ProposalVoted { proposal_id: 4, account_id: &bob, approve: false },
+                     "proposal_voted", r#"{"proposal_id":4,"account_id":"bob","approve":false}"#);
+        assert_event(Event::ProposalExecuted { proposal_id: 4 },
+                     "proposal_executed", r#"{"proposal_id":4}"#);
+        assert_event(Event::ProposalRejected { proposal_id: 4 },
+                     "proposal_rejected", r#"{"proposal_id":4}"#);
+        assert_event(Event::GovernanceUpdated { approval_threshold: Some(2), proposal_period: 1000 },
+                     "governance_updated", r#"{"approval_threshold":2,"proposal_period":1000}"#);
+        assert_event(Event::GovernanceUpdated { approval_threshold: None, proposal_period: 1000 },
+                     "governance_updated", r#"{"approval_threshold":null,"proposal_period":1000}"#);
+        assert_event(Event::RoleGranted { account_id: &bob, role: Role::Moderator, granted_by_account_id: &alice },
+                     "role_granted", r#"{"account_id":"bob","role":"Moderator","granted_by_account_id":"alice"}"#);
+        assert_event(Event::RoleRevoked { account_id: &bob, role: Role::Curator, revoked_by_account_id: &alice },
+                     "role_revoked", r#"{"account_id":"bob","role":"Curator","revoked_by_account_id":"alice"}"#);
+        assert_event(Event::ContractUpgraded { code_hash: Base58CryptoHash::from([0; 32]), upgraded_by_account_id: &alice },
+                     "contract_upgraded", r#"{"code_hash":"11111111111111111111111111111111","upgraded_by_account_id":"alice"}"#);
+    }
+
+    // storage settlement may log next to the events
+    fn event_logs() -> Vec<String> {
+        get_logs().into_iter().filter(|log| log.starts_with("EVENT_JSON:")).collect()
+    }
+
+    #[test]
+    fn entrypoints_emit_app_events() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+        assert_eq!(event_logs(), vec![event_log("app_added", r#"{"app_id":0,"slug":"dapp","dapp_account_id":"dapp.near","added_by_account_id":"bob"}"#)]);
+
+        set_predecessor(&guardian());
+        contract.update_app(app_id, app_json("dapp", vec![]));
+        assert_eq!(event_logs(), vec![event_log("app_updated", r#"{"app_id":0,"slug":"dapp","updated_by_account_id":"alice"}"#)]);
+
+        set_predecessor(&guardian());
+        contract.disable_app(app_id);
+        assert_eq!(event_logs(), vec![event_log("app_disabled", r#"{"app_id":0,"disabled_by_account_id":"alice"}"#)]);
+
+        set_predecessor(&guardian());
+        contract.enable_app(app_id);
+        assert_eq!(event_logs(), vec![event_log("app_enabled", r#"{"app_id":0,"enabled_by_account_id":"alice"}"#)]);
+
+        set_predecessor(&author());
+        contract.delete_app(app_id);
+        assert_eq!(event_logs(), vec![event_log("app_deleted", r#"{"app_id":0,"slug":"dapp","deleted_by_account_id":"bob"}"#)]);
+    }
+
+    #[test]
+    fn entrypoints_emit_category_and_governance_events() {
+        let mut contract = new_contract();
+        add_category(&mut contract, "games");
+        assert_eq!(event_logs(), vec![event_log("category_added", r#"{"category_id":0,"slug":"games","title":"Category games","parent_id":null}"#)]);
+
+        set_predecessor(&guardian());
+        contract.hide_category(0, true);
+        assert_eq!(event_logs(), vec![event_log("category_updated", r#"{"category_id":0,"slug":"games","title":"Category games","order":0,"hidden":true,"parent_id":null}"#)]);
+
+        // a single guardian executes the proposal right away
+        set_predecessor(&guardian());
+        contract.add_guardian(author());
+        assert_eq!(event_logs(), vec![
+            event_log("proposal_added", r#"{"proposal_id":0,"proposer_id":"alice"}"#),
+            event_log("proposal_executed", r#"{"proposal_id":0}"#),
+            event_log("guardian_added", r#"{"account_id":"bob","added_by_account_id":"alice"}"#),
+        ]);
+
+        set_predecessor(&guardian());
+        contract.remove_guardian(author());
+        set_predecessor(&author());
+        contract.reject_proposal(1);
+        assert_eq!(event_logs(), vec![
+            event_log("proposal_voted", r#"{"proposal_id":1,"account_id":"bob","approve":false}"#),
+            event_log("proposal_rejected", r#"{"proposal_id":1}"#),
+        ]);
+    }
+}
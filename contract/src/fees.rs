@@ -0,0 +1,117 @@
+use near_sdk::is_promise_success;
+use crate::*;
+
+const GAS_FOR_AFTER_WITHDRAW_FEES: Gas = Gas(Gas::ONE_TERA.0 * 5);
+
+pub const DEFAULT_LISTING_FEE: Balance = 100_000_000_000_000_000_000_000; // 0.1 NEAR
+pub const DEFAULT_UPDATE_FEE: Balance = 100_000_000_000_000_000_000_000; // 0.1 NEAR
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FeesJSON {
+    pub listing_fee: WrappedBalance,
+    pub update_fee: WrappedBalance,
+    pub treasury: WrappedBalance,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_fees(&self) -> FeesJSON {
+        FeesJSON {
+            listing_fee: WrappedBalance::from(self.listing_fee),
+            update_fee: WrappedBalance::from(self.update_fee),
+            treasury: WrappedBalance::from(self.treasury),
+        }
+    }
+
+    pub fn set_fees(&mut self, listing_fee: WrappedBalance, update_fee: WrappedBalance) -> ProposalId {
+        self.add_proposal(ProposalAction::SetFees { listing_fee, update_fee })
+    }
+
+    pub fn withdraw_fees(&mut self, receiver_id: AccountId, amount: WrappedBalance) -> ProposalId {
+        require!(amount.0 > 0, "ERR_ZERO_AMOUNT");
+        self.add_proposal(ProposalAction::WithdrawFees { receiver_id, amount })
+    }
+
+    // the treasury is debited before the transfer, so a failed transfer gives the amount back
+    #[private]
+    pub fn after_withdraw_fees(&mut self, amount: WrappedBalance) -> bool {
+        if is_promise_success() {
+            true
+        } else {
+            self.treasury += amount.0;
+            log!("ERR_WITHDRAW_FEES_FAILED");
+            false
+        }
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_set_fees(&mut self, listing_fee: WrappedBalance, update_fee: WrappedBalance) {
+        self.listing_fee = listing_fee.0;
+        self.update_fee = update_fee.0;
+
+        Event::FeesUpdated { listing_fee, update_fee }.emit();
+    }
+
+    pub(crate) fn internal_withdraw_fees(&mut self, receiver_id: AccountId, amount: WrappedBalance, withdrawn_by_account_id: &AccountId) -> Promise {
+        require!(amount.0 <= self.treasury, "ERR_NOT_ENOUGH_FEES");
+
+        // the balance left after the withdrawal must still cover storage staking
+        let storage_reserve = Balance::from(env::storage_usage()) * env::storage_byte_cost();
+        require!(env::account_balance().saturating_sub(amount.0) >= storage_reserve, "ERR_STORAGE_RESERVE");
+
+        self.treasury -= amount.0;
+
+        Event::FeesWithdrawn { receiver_id: &receiver_id, amount, withdrawn_by_account_id }.emit();
+
+        Promise::new(receiver_id).transfer(amount.0).then(
+            ext_self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_AFTER_WITHDRAW_FEES)
+                .after_withdraw_fees(amount)
+        )
+    }
+}
+
+pub(crate) fn format_near_amount(amount: Balance) -> String {
+    let near = amount / 10u128.pow(24);
+    let fraction = format!("{:024}", amount % 10u128.pow(24));
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        near.to_string()
+    } else {
+        format!("{}.{}", near, fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::{testing_env, PromiseResult, RuntimeFeesConfig, VMConfig};
+    use crate::test_utils::*;
+    use super::*;
+
+    fn withdraw_result(contract: &mut Contract, amount: Balance, result: PromiseResult) -> bool {
+        testing_env!(
+            context(&contract_account_id()).build(),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![result]
+        );
+        contract.after_withdraw_fees(WrappedBalance::from(amount))
+    }
+
+    #[test]
+    fn failed_withdrawal_returns_fees_to_treasury() {
+        let mut contract = new_contract();
+        contract.treasury = 10;
+        set_predecessor(&guardian());
+        contract.withdraw_fees(author(), WrappedBalance::from(4));
+        assert_eq!(contract.treasury, 6);
+
+        assert!(!withdraw_result(&mut contract, 4, PromiseResult::Failed));
+        assert_eq!(contract.treasury, 10);
+        assert!(withdraw_result(&mut contract, 4, PromiseResult::Successful(vec![])));
+        assert_eq!(contract.treasury, 10);
+    }
+}
@@ -0,0 +1,289 @@
+use crate::*;
+use near_sdk::json_types::Base58CryptoHash;
+
+pub const DEFAULT_PROPOSAL_PERIOD: u64 = 7 * 24 * 60 * 60 * 1_000_000_000; // 7 days in nanoseconds
+
+pub type ProposalId = u64;
+
+// sensitive actions are executed only after approval_threshold guardians have approved them,
+// a majority of the current guardians unless a fixed threshold is set
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ProposalAction {
+    AddGuardian { account_id: AccountId },
+    RemoveGuardian { account_id: AccountId },
+    SetFees { listing_fee: WrappedBalance, update_fee: WrappedBalance },
+    WithdrawFees { receiver_id: AccountId, amount: WrappedBalance },
+    DeleteApp { app_id: AppId },
+    SetGovernance { approval_threshold: Option<u64>, proposal_period: u64 },
+    GrantRole { account_id: AccountId, role: Role },
+    RevokeRole { account_id: AccountId, role: Role },
+    Upgrade { code_hash: Base58CryptoHash },
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ProposalStatus {
+    InProgress,
+    Executed,
+    Rejected,
+    Expired,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Proposal {
+    pub proposer_id: AccountId,
+    pub action: ProposalAction,
+    pub approvals: Vec<AccountId>,
+    pub rejections: Vec<AccountId>,
+    pub status: ProposalStatus,
+    pub expires_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GovernanceJSON {
+    pub approval_threshold: Option<u64>,
+    pub effective_approval_threshold: u64,
+    pub proposal_period: u64,
+}
+
+impl Proposal {
+    // expiration is not written to the state, it is resolved on read
+    pub(crate) fn current_status(&self) -> ProposalStatus {
+        if self.status == ProposalStatus::InProgress && env::block_timestamp() >= self.expires_at {
+            ProposalStatus::Expired
+        } else {
+            self.status
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_governance(&self) -> GovernanceJSON {
+        GovernanceJSON {
+            approval_threshold: self.approval_threshold,
+            effective_approval_threshold: self.internal_approval_threshold(),
+            proposal_period: self.proposal_period,
+        }
+    }
+
+    pub fn get_proposal(&self, proposal_id: ProposalId) -> Proposal {
+        let mut proposal = self.proposals.get(&proposal_id).expect("ERR_NO_PROPOSAL");
+        proposal.status = proposal.current_status();
+        proposal
+    }
+
+    pub fn get_proposals(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<(ProposalId, Proposal)> {
+        unordered_map_pagination(&self.proposals, from_index, limit)
+            .into_iter()
+            .map(|(proposal_id, mut proposal): (ProposalId, Proposal)| {
+                proposal.status = proposal.current_status();
+                (proposal_id, proposal)
+            })
+            .collect()
+    }
+
+    // the proposer's approval is counted right away, so a single guardian or a threshold of 1 executes the action immediately.
+    // treasurers may propose withdrawals, but only owners' approvals are counted
+    pub fn add_proposal(&mut self, action: ProposalAction) -> ProposalId {
+        if let ProposalAction::WithdrawFees { .. } = action {
+            self.assert_role(Role::Treasurer);
+        } else {
+            self.assert_guardian();
+        }
+        let proposer_id = env::predecessor_account_id();
+
+        let proposal_id = self.next_proposal_id;
+        self.next_proposal_id += 1;
+
+        Event::ProposalAdded { proposal_id, proposer_id: &proposer_id }.emit();
+
+        let proposal = Proposal {
+            proposer_id: proposer_id.clone(),
+            action,
+            approvals: vec![proposer_id],
+            rejections: vec![],
+            status: ProposalStatus::InProgress,
+            expires_at: env::block_timestamp() + self.proposal_period,
+        };
+        self.internal_execute_proposal_if_approved(proposal_id, proposal);
+
+        proposal_id
+    }
+
+    pub fn approve_proposal(&mut self, proposal_id: ProposalId) {
+        let mut proposal = self.internal_get_proposal_for_vote(proposal_id);
+        proposal.approvals.push(env::predecessor_account_id());
+
+        Event::ProposalVoted { proposal_id, account_id: &env::predecessor_account_id(), approve: true }.emit();
+
+        self.internal_execute_proposal_if_approved(proposal_id, proposal);
+    }
+
+    pub fn reject_proposal(&mut self, proposal_id: ProposalId) {
+        let mut proposal = self.internal_get_proposal_for_vote(proposal_id);
+        proposal.rejections.push(env::predecessor_account_id());
+
+        Event::ProposalVoted { proposal_id, account_id: &env::predecessor_account_id(), approve: false }.emit();
+
+        // proposal is rejected once the remaining guardians can't reach the threshold
+        let rejections = self.internal_count_guardian_votes(&proposal.rejections);
+        if self.guardians.len().saturating_sub(rejections) < self.internal_approval_threshold() {
+            proposal.status = ProposalStatus::Rejected;
+            Event::ProposalRejected { proposal_id }.emit();
+        }
+
+        self.proposals.insert(&proposal_id, &proposal);
+    }
+
+    // approval_threshold of None switches back to the majority of guardians
+    pub fn set_governance(&mut self, approval_threshold: Option<u64>, proposal_period: u64) -> ProposalId {
+        self.add_proposal(ProposalAction::SetGovernance { approval_threshold, proposal_period })
+    }
+
+    // finalized and expired proposals only keep their events, any guardian may clear them from the state
+    pub fn remove_proposal(&mut self, proposal_id: ProposalId) {
+        self.assert_guardian();
+        let proposal = self.proposals.get(&proposal_id).expect("ERR_NO_PROPOSAL");
+        require!(proposal.current_status() != ProposalStatus::InProgress, "ERR_PROPOSAL_IN_PROGRESS");
+        self.proposals.remove(&proposal_id);
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_approval_threshold(&self) -> u64 {
+        self.approval_threshold.unwrap_or(self.guardians.len() / 2 + 1)
+    }
+
+    fn internal_get_proposal_for_vote(&self, proposal_id: ProposalId) -> Proposal {
+        self.assert_guardian();
+        let proposal = self.proposals.get(&proposal_id).expect("ERR_NO_PROPOSAL");
+        match proposal.current_status() {
+            ProposalStatus::InProgress => (),
+            ProposalStatus::Expired => env::panic_str("ERR_PROPOSAL_EXPIRED"),
+            _ => env::panic_str("ERR_PROPOSAL_IS_FINALIZED"),
+        }
+
+        let account_id = env::predecessor_account_id();
+        require!(!proposal.approvals.contains(&account_id) && !proposal.rejections.contains(&account_id), "ERR_ALREADY_VOTED");
+        proposal
+    }
+
+    // votes of removed guardians are ignored
+    fn internal_count_guardian_votes(&self, votes: &[AccountId]) -> u64 {
+        votes.iter().filter(|account_id| self.guardians.contains(account_id)).count() as u64
+    }
+
+    fn internal_execute_proposal_if_approved(&mut self, proposal_id: ProposalId, mut proposal: Proposal) {
+        if self.internal_count_guardian_votes(&proposal.approvals) >= self.internal_approval_threshold() {
+            proposal.status = ProposalStatus::Executed;
+            self.proposals.insert(&proposal_id, &proposal);
+
+            Event::ProposalExecuted { proposal_id }.emit();
+
+            self.internal_execute_proposal_action(proposal.action, &proposal.proposer_id);
+        } else {
+            self.proposals.insert(&proposal_id, &proposal);
+        }
+    }
+
+    fn internal_execute_proposal_action(&mut self, action: ProposalAction, proposer_id: &AccountId) {
+        match action {
+            ProposalAction::AddGuardian { account_id } => self.internal_add_guardian(&account_id, proposer_id),
+            ProposalAction::RemoveGuardian { account_id } => self.internal_remove_guardian(&account_id, proposer_id),
+            ProposalAction::SetFees { listing_fee, update_fee } => self.internal_set_fees(listing_fee, update_fee),
+            ProposalAction::WithdrawFees { receiver_id, amount } => {
+                self.internal_withdraw_fees(receiver_id, amount, proposer_id);
+            }
+            ProposalAction::DeleteApp { app_id } => self.internal_delete_app(app_id, proposer_id),
+            ProposalAction::SetGovernance { approval_threshold, proposal_period } => {
+                if let Some(approval_threshold) = approval_threshold {
+                    require!(approval_threshold > 0 && approval_threshold <= self.guardians.len(), "ERR_INVALID_APPROVAL_THRESHOLD");
+                }
+                require!(proposal_period > 0, "ERR_INVALID_PROPOSAL_PERIOD");
+                self.approval_threshold = approval_threshold;
+                self.proposal_period = proposal_period;
+
+                Event::GovernanceUpdated { approval_threshold, proposal_period }.emit();
+            }
+            ProposalAction::GrantRole { account_id, role } => self.internal_grant_role(&account_id, role, proposer_id),
+            ProposalAction::RevokeRole { account_id, role } => self.internal_revoke_role(&account_id, role, proposer_id),
+            ProposalAction::Upgrade { code_hash } => {
+                self.internal_upgrade(code_hash);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::*;
+    use super::*;
+
+    fn add_guardians(contract: &mut Contract) {
+        set_predecessor(&guardian());
+        contract.add_guardian(author());
+        let proposal_id = contract.add_guardian(stranger());
+        set_predecessor(&author());
+        contract.approve_proposal(proposal_id);
+        assert_eq!(contract.guardians.len(), 3);
+    }
+
+    #[test]
+    fn proposals_need_majority_of_guardians_by_default() {
+        let mut contract = new_contract();
+        assert_eq!(contract.internal_approval_threshold(), 1);
+        add_guardians(&mut contract);
+        assert_eq!(contract.internal_approval_threshold(), 2);
+
+        set_predecessor(&guardian());
+        let proposal_id = contract.set_fees(WrappedBalance::from(1), WrappedBalance::from(1));
+        assert!(contract.get_proposal(proposal_id).status == ProposalStatus::InProgress);
+        set_predecessor(&stranger());
+        contract.approve_proposal(proposal_id);
+        assert!(contract.get_proposal(proposal_id).status == ProposalStatus::Executed);
+        assert_eq!(contract.listing_fee, 1);
+    }
+
+    #[test]
+    fn fixed_threshold_replaces_majority() {
+        let mut contract = new_contract();
+        add_guardians(&mut contract);
+        set_predecessor(&guardian());
+        let proposal_id = contract.set_governance(Some(3), DEFAULT_PROPOSAL_PERIOD);
+        set_predecessor(&author());
+        contract.approve_proposal(proposal_id);
+        assert_eq!(contract.get_governance().effective_approval_threshold, 3);
+
+        set_predecessor(&guardian());
+        let proposal_id = contract.set_governance(None, DEFAULT_PROPOSAL_PERIOD);
+        set_predecessor(&author());
+        contract.approve_proposal(proposal_id);
+        set_predecessor(&stranger());
+        contract.approve_proposal(proposal_id);
+        assert_eq!(contract.get_governance().approval_threshold, None);
+        assert_eq!(contract.get_governance().effective_approval_threshold, 2);
+    }
+
+    #[test]
+    fn finalized_proposals_are_removed() {
+        let mut contract = new_contract();
+        set_predecessor(&guardian());
+        let proposal_id = contract.set_fees(WrappedBalance::from(1), WrappedBalance::from(1));
+        contract.remove_proposal(proposal_id);
+        assert!(contract.proposals.get(&proposal_id).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_PROPOSAL_IN_PROGRESS")]
+    fn open_proposals_are_kept() {
+        let mut contract = new_contract();
+        add_guardians(&mut contract);
+        set_predecessor(&guardian());
+        let proposal_id = contract.set_fees(WrappedBalance::from(1), WrappedBalance::from(1));
+        contract.remove_proposal(proposal_id);
+    }
+}
@@ -2,40 +2,91 @@ use crate::*;
 
 #[near_bindgen]
 impl Contract {
-    pub fn set_disabled_apps_number(&mut self, disabled_apps: u64) {
-        self.assert_guardian();
-        self.disabled_apps = disabled_apps;
+    // walks app ids in chunks starting from 0, returns the next from_index until the walk is finished
+    // apps changed behind the cursor meanwhile update the partial count, see internal_increase_active_apps
+    pub fn recount_apps(&mut self, from_index: AppId, limit: u64) -> Option<AppId> {
+        self.assert_role(Role::Curator);
+        let mut active_apps = if from_index == 0 {
+            0
+        } else {
+            let (next_app_id, active_apps) = self.recounted_active_apps.expect("ERR_RECOUNT_NOT_STARTED");
+            require!(next_app_id == from_index, "ERR_WRONG_RECOUNT_INDEX");
+            active_apps
+        };
+
+        let next_index = from_index.saturating_add(limit).min(self.next_app_id);
+        for app_id in from_index..next_index {
+            if let Some(app) = self.apps.get(&app_id) {
+                if App::from(app).active.unwrap_or(true) {
+                    active_apps += 1;
+                }
+            }
+        }
+
+        if next_index < self.next_app_id {
+            self.recounted_active_apps = Some((next_index, active_apps));
+            Some(next_index)
+        } else {
+            self.recounted_active_apps = None;
+            self.active_apps = active_apps;
+            Event::AppsRecounted { active_apps }.emit();
+            None
+        }
+    }
+
+    // rebuild indexes for apps added before the indexes were introduced
+    pub fn rebuild_app_indexes(&mut self, from_index: Option<u64>, limit: Option<u64>) {
+        self.assert_role(Role::Curator);
+        for (app_id, app) in self.internal_get_apps(from_index, limit) {
+            self.internal_remove_app_from_search_index(app_id, &app);
+            if app.active.unwrap_or(true) {
+                self.internal_add_app_to_search_index(app_id, &app);
+                self.active_app_ids.insert(&app_id);
+                self.disabled_app_ids.remove(&app_id);
+            } else {
+                self.active_app_ids.remove(&app_id);
+                self.disabled_app_ids.insert(&app_id);
+            }
+            self.internal_add_app_to_author(app_id, &app.added_by_account_id);
+        }
     }
 
     pub fn get_guardians(&self) -> Vec<AccountId> {
         self.guardians.to_vec()
     }
 
-    pub fn add_guardian(&mut self, account_id: AccountId) {
-        self.assert_guardian();
-        self.guardians.insert(&account_id);
+    pub fn add_guardian(&mut self, account_id: AccountId) -> ProposalId {
+        self.add_proposal(ProposalAction::AddGuardian { account_id })
     }
 
-    pub fn remove_guardian(&mut self, account_id: AccountId) {
-        self.assert_guardian();
-        require!(self.guardians.len() > 1, "ERR_LAST_GUARDIAN");
-        self.guardians.remove(&account_id);
+    pub fn remove_guardian(&mut self, account_id: AccountId) -> ProposalId {
+        self.add_proposal(ProposalAction::RemoveGuardian { account_id })
     }
 
-    pub fn add_category(&mut self, title: String, slug: String) {
-        self.assert_guardian();
+    pub fn add_category(&mut self, title: String, slug: String, parent_id: Option<CategoryId>) {
+        self.assert_role(Role::Curator);
+        self.assert_category_parent(None, parent_id);
 
-        for category_id in 0..self.next_category_id {
-            let category: Category = self.categories.get(&category_id).expect("ERR_NO_CATEGORY").into();
-            require!(category.slug != slug.clone(), "ERR_SLUG_ALREADY_EXISTS");
-            require!(category.title != title.clone(), "ERR_TITLE_ALREADY_EXISTS");
-        }
+        require!(self.category_id_by_slug.get(&slug).is_none(), "ERR_SLUG_ALREADY_EXISTS");
+        require!(self.category_id_by_title.get(&title).is_none(), "ERR_TITLE_ALREADY_EXISTS");
 
         let category = Category {
             slug,
             title,
+            order: 0,
+            hidden: false,
+            parent_id,
         };
 
+        Event::CategoryAdded {
+            category_id: self.next_category_id,
+            slug: &category.slug,
+            title: &category.title,
+            parent_id: category.parent_id,
+        }.emit();
+
+        self.category_id_by_slug.insert(&category.slug, &self.next_category_id);
+        self.category_id_by_title.insert(&category.title, &self.next_category_id);
         self.categories.insert(&self.next_category_id, &VCategory::Current(category));
 
         self.apps_ids_by_category_id.insert(&self.next_category_id, &UnorderedSet::new(StorageKey::AppIdsSetInCategoryId { category_id: self.next_category_id }));
@@ -43,86 +94,516 @@ impl Contract {
         self.next_category_id += 1;
     }
 
+    pub fn update_category(&mut self, category_id: CategoryId, title: String, slug: String) {
+        self.assert_role(Role::Curator);
+        let mut category: Category = self.categories.get(&category_id).expect("ERR_NO_CATEGORY").into();
+
+        if category.slug != slug {
+            require!(self.category_id_by_slug.get(&slug).is_none(), "ERR_SLUG_ALREADY_EXISTS");
+            self.category_id_by_slug.remove(&category.slug);
+            self.category_id_by_slug.insert(&slug, &category_id);
+            category.slug = slug;
+        }
+
+        if category.title != title {
+            require!(self.category_id_by_title.get(&title).is_none(), "ERR_TITLE_ALREADY_EXISTS");
+            self.category_id_by_title.remove(&category.title);
+            self.category_id_by_title.insert(&title, &category_id);
+            category.title = title;
+        }
+
+        Event::category_updated(category_id, &category);
+        self.categories.insert(&category_id, &VCategory::Current(category));
+    }
+
+    pub fn set_category_order(&mut self, category_id: CategoryId, order: u64) {
+        self.assert_role(Role::Curator);
+        let mut category: Category = self.categories.get(&category_id).expect("ERR_NO_CATEGORY").into();
+        category.order = order;
+        Event::category_updated(category_id, &category);
+        self.categories.insert(&category_id, &VCategory::Current(category));
+    }
+
+    pub fn set_category_parent(&mut self, category_id: CategoryId, parent_id: Option<CategoryId>) {
+        self.assert_role(Role::Curator);
+        let mut category: Category = self.categories.get(&category_id).expect("ERR_NO_CATEGORY").into();
+        self.assert_category_parent(Some(category_id), parent_id);
+        category.parent_id = parent_id;
+        Event::category_updated(category_id, &category);
+        self.categories.insert(&category_id, &VCategory::Current(category));
+    }
+
+    pub fn hide_category(&mut self, category_id: CategoryId, hidden: bool) {
+        self.assert_role(Role::Curator);
+        let mut category: Category = self.categories.get(&category_id).expect("ERR_NO_CATEGORY").into();
+        category.hidden = hidden;
+        Event::category_updated(category_id, &category);
+        self.categories.insert(&category_id, &VCategory::Current(category));
+    }
+
+    pub fn remove_category(&mut self, category_id: CategoryId) {
+        self.assert_role(Role::Curator);
+        let category: Category = self.categories.get(&category_id).expect("ERR_NO_CATEGORY").into();
+
+        // disabled apps are not in the category set, so they are detached separately
+        let mut apps_ids_by_category_id = self.apps_ids_by_category_id.get(&category_id).expect("ERR_NO_DATA");
+        for app_id in apps_ids_by_category_id.to_vec().into_iter().chain(self.disabled_app_ids.to_vec()) {
+            let mut app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+            if app.categories.remove(&category_id) {
+                self.apps.insert(&app_id, &VApp::Current(app));
+            }
+        }
+        apps_ids_by_category_id.clear();
+        self.apps_ids_by_category_id.remove(&category_id);
+
+        let category_string = category_id.to_string();
+        for (app_id, mut pending_app) in self.pending_apps.to_vec() {
+            if pending_app.app.categories.contains(&category_string) {
+                pending_app.app.categories.retain(|pending_category| pending_category != &category_string);
+                self.pending_apps.insert(&app_id, &pending_app);
+            }
+        }
+
+        // move sub-categories one level up
+        for (child_id, v_child) in self.categories.to_vec() {
+            let mut child: Category = v_child.into();
+            if child.parent_id == Some(category_id) {
+                child.parent_id = category.parent_id;
+                Event::category_updated(child_id, &child);
+                self.categories.insert(&child_id, &VCategory::Current(child));
+            }
+        }
+
+        self.category_id_by_slug.remove(&category.slug);
+        self.category_id_by_title.remove(&category.title);
+        self.categories.remove(&category_id);
+
+        Event::CategoryRemoved { category_id, slug: &category.slug }.emit();
+    }
+
     pub fn disable_app(&mut self, app_id: AppId) {
-        self.assert_guardian();
+        self.assert_role(Role::Moderator);
         let mut app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
 
-        if app.active == Some(true) {
-            self.disabled_apps += 1;
+        if app.active.unwrap_or(true) {
+            self.internal_decrease_active_apps(app_id);
         }
 
         app.active = Some(false);
 
+        self.internal_remove_app_from_search_index(app_id, &app);
+
+        Event::AppDisabled { app_id, disabled_by_account_id: &env::predecessor_account_id() }.emit();
+
         // clear categories for disabled app to keep proper counters value
+        self.internal_remove_app_from_categories(app_id, &app);
+        self.active_app_ids.remove(&app_id);
+        self.disabled_app_ids.insert(&app_id);
+
+        self.apps.insert(&app_id, &VApp::Current(app));
+    }
+
+    pub fn enable_app(&mut self, app_id: AppId) {
+        self.assert_role(Role::Moderator);
+        let mut app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+        require!(app.active == Some(false), "ERR_APP_IS_ACTIVE");
+
+        self.internal_increase_active_apps(app_id);
+
+        app.active = Some(true);
+
+        // categories removed while the app was disabled are dropped
         for category_id in app.categories.to_vec() {
-            if self.categories.get(&category_id).is_some() {
-                let mut apps_ids_by_category_id = self.apps_ids_by_category_id.get(&category_id).expect("ERR_NO_DATA");
-                apps_ids_by_category_id.remove(&app_id);
-                self.apps_ids_by_category_id.insert(&category_id, &apps_ids_by_category_id);
+            if self.categories.get(&category_id).is_none() {
+                app.categories.remove(&category_id);
             }
         }
+        self.internal_add_app_to_categories(app_id, &app);
+        self.internal_add_app_to_search_index(app_id, &app);
+        self.active_app_ids.insert(&app_id);
+        self.disabled_app_ids.remove(&app_id);
+
+        Event::AppEnabled { app_id, enabled_by_account_id: &env::predecessor_account_id() }.emit();
 
         self.apps.insert(&app_id, &VApp::Current(app));
     }
 
+    // web4 admin forms can only send strings, so apps are found by their dapp accounts
+    pub fn disable_app_by_account_id(&mut self, account_id: AccountId) {
+        let app_id = self.app_id_by_dapp_account_id.get(&account_id).expect("ERR_ACCOUNT_ID");
+        self.disable_app(app_id);
+    }
+
+    pub fn enable_app_by_account_id(&mut self, account_id: AccountId) {
+        let app_id = self.app_id_by_dapp_account_id.get(&account_id).expect("ERR_ACCOUNT_ID");
+        self.enable_app(app_id);
+    }
+
     #[payable]
     pub fn update_app(&mut self, app_id: AppId, mut app: AppJSON) {
-        let edit_by_guardian = self.guardians.contains(&env::predecessor_account_id());
-        let edit_by_author = app.added_by_account_id == Some(env::predecessor_account_id());
-        if !(edit_by_guardian || edit_by_author) {
-            env::panic_str("ERR_NO_ACCESS")
-        }
         let old_app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+        let edit_by_guardian = self.has_role(&env::predecessor_account_id(), Role::Moderator);
+        let edit_by_author = old_app.added_by_account_id == env::predecessor_account_id()
+            || self.is_app_maintainer(app_id, &env::predecessor_account_id());
+        require!(edit_by_guardian || edit_by_author, "ERR_NO_ACCESS");
 
         if !edit_by_guardian {
             // EDIT BY AUTHOR, NOT GUARDIAN
-            require!(env::attached_deposit() >= LISTING_FEE, "ERR_LISTING_FEE_REQUIRED");
+            require!(env::attached_deposit() >= self.update_fee, "ERR_UPDATE_FEE_REQUIRED");
+            app.active = old_app.active;
+        } else if app.active.is_none() {
             app.active = old_app.active;
         }
 
         require!(old_app.dapp_account_id == app.dapp_account_id, "ERR_CANT_UPDATE_ACCOUNT_ID");
 
-        if old_app.active == Some(false) && self.disabled_apps > 0 {
-            self.disabled_apps -= 1;
+        let fee = if edit_by_guardian { 0 } else { self.update_fee };
+
+        if !edit_by_guardian && self.moderation_enabled {
+            self.assert_slug_available(&filter_slug(app.slug.clone()).to_lowercase(), &old_app.added_by_account_id, Some(app_id));
+            let storage_deposit = self.app_storage_deposits.get(&app_id).unwrap_or(0);
+            require!(env::attached_deposit() >= fee + estimate_app_storage_cost(&app).saturating_sub(storage_deposit), "ERR_NOT_ENOUGH_STORAGE_DEPOSIT");
+            self.internal_add_pending_app(app_id, app, env::predecessor_account_id(), WrappedBalance::from(env::attached_deposit()), WrappedBalance::from(fee), true);
+            return;
+        }
+
+        self.treasury += fee;
+        let author_id = old_app.added_by_account_id.clone();
+        let storage_usage_before = env::storage_usage();
+        self.internal_update_app(app_id, app, old_app);
+        self.internal_settle_app_storage(app_id, storage_usage_before, env::attached_deposit() - fee, &env::predecessor_account_id(), &author_id, edit_by_guardian);
+    }
+
+    // web4 forms send every value as a string, so the app is found by its dapp account
+    #[payable]
+    pub fn update_app_by_account_id(&mut self, app: AppJSON) {
+        let app_id = self.app_id_by_dapp_account_id.get(&app.dapp_account_id).expect("ERR_ACCOUNT_ID");
+        self.update_app(app_id, app);
+    }
+
+    // authors delete their apps right away, guardians create a proposal
+    pub fn delete_app(&mut self, app_id: AppId) -> Option<ProposalId> {
+        let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+        if app.added_by_account_id == env::predecessor_account_id() {
+            self.internal_delete_app(app_id, &env::predecessor_account_id());
+            None
+        } else {
+            Some(self.add_proposal(ProposalAction::DeleteApp { app_id }))
+        }
+    }
+
+    // expired tombstones can be cleared by anyone, moderators may release a slug before that
+    pub fn remove_slug_tombstone(&mut self, slug: Slug) {
+        let tombstone = self.slug_tombstones.get(&slug).expect("ERR_NO_SLUG_TOMBSTONE");
+        if tombstone.expires_at > env::block_timestamp() {
+            self.assert_role(Role::Moderator);
+        }
+        self.slug_tombstones.remove(&slug);
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_add_guardian(&mut self, account_id: &AccountId, added_by_account_id: &AccountId) {
+        self.guardians.insert(account_id);
+
+        Event::GuardianAdded { account_id, added_by_account_id }.emit();
+    }
+
+    pub(crate) fn internal_remove_guardian(&mut self, account_id: &AccountId, removed_by_account_id: &AccountId) {
+        require!(self.guardians.contains(account_id), "ERR_NOT_A_GUARDIAN");
+        require!(self.guardians.len() > 1, "ERR_LAST_GUARDIAN");
+        if let Some(approval_threshold) = self.approval_threshold {
+            require!(self.guardians.len() > approval_threshold, "ERR_GUARDIANS_BELOW_APPROVAL_THRESHOLD");
         }
+        self.guardians.remove(account_id);
+
+        Event::GuardianRemoved { account_id, removed_by_account_id }.emit();
+    }
 
-        /* REMOVE OLD DATA */
-        self.app_id_by_slug.remove(&old_app.slug);
-        self.app_id_by_dapp_account_id.remove(&old_app.dapp_account_id);
+    // a running recount has already walked past lower app ids, so their changes are applied to its partial count too
+    pub(crate) fn internal_increase_active_apps(&mut self, app_id: AppId) {
+        self.active_apps += 1;
+        if let Some((next_app_id, active_apps)) = self.recounted_active_apps.as_mut() {
+            if app_id < *next_app_id {
+                *active_apps += 1;
+            }
+        }
+    }
 
-        for category_id in old_app.categories.to_vec() {
-            if self.categories.get(&category_id).is_some() {
-                let mut apps_ids_by_category_id = self.apps_ids_by_category_id.get(&category_id).expect("ERR_NO_DATA");
-                apps_ids_by_category_id.remove(&app_id);
-                self.apps_ids_by_category_id.insert(&category_id, &apps_ids_by_category_id);
+    pub(crate) fn internal_decrease_active_apps(&mut self, app_id: AppId) {
+        self.active_apps = self.active_apps.saturating_sub(1);
+        if let Some((next_app_id, active_apps)) = self.recounted_active_apps.as_mut() {
+            if app_id < *next_app_id {
+                *active_apps = active_apps.saturating_sub(1);
             }
         }
+    }
 
-        /* CREATE NEW DATA */
-        let mut categories = old_app.categories;
-        categories.clear();
-        for category_string in app.categories.clone() {
-            let category_id: CategoryId = category_string.parse().expect("ERR_WRONG_CATEGORY");
-            if self.categories.get(&category_id).is_some() {
-                let mut apps_ids_by_category_id = self.apps_ids_by_category_id.get(&category_id).expect("ERR_NO_DATA");
-                apps_ids_by_category_id.insert(&app_id);
-                self.apps_ids_by_category_id.insert(&category_id, &apps_ids_by_category_id);
-                categories.insert(&category_id);
+    pub(crate) fn internal_delete_app(&mut self, app_id: AppId, deleted_by_account_id: &AccountId) {
+        let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+
+        self.pending_app_owners.remove(&app_id);
+        for account_id in self.get_app_maintainers(app_id) {
+            self.internal_remove_app_maintainer(app_id, &account_id);
+        }
+        if let Some(pending_app) = self.pending_apps.remove(&app_id) {
+            if pending_app.deposit.0 > 0 {
+                Promise::new(pending_app.submitted_by_account_id).transfer(pending_app.deposit.0);
             }
         }
-        let mut contracts = old_app.contracts;
-        contracts.clear();
-        let contracts_vec = if let Some(contracts_vec) = app.contracts.clone() { contracts_vec } else { vec![] };
-        for contract in contracts_vec {
-            contracts.insert(&contract);
+
+        if app.active.unwrap_or(true) {
+            self.internal_decrease_active_apps(app_id);
         }
 
-        let added_by_account_id = if app.added_by_account_id.is_some() {
-            app.added_by_account_id.clone()
-        } else {
-            Some(old_app.added_by_account_id)
-        };
+        Event::AppDeleted { app_id, slug: &app.slug, deleted_by_account_id }.emit();
+
+        // freed storage goes back to the author up to the amount they have paid
+        let author_id = app.added_by_account_id.clone();
+        let storage_usage_before = env::storage_usage();
+        self.internal_remove_app(app_id, app);
+        self.internal_settle_app_storage(app_id, storage_usage_before, 0, &author_id, &author_id, true);
+        self.app_storage_deposits.remove(&app_id);
+    }
+}
+#[cfg(test)]
+mod tests {
+    use near_sdk::testing_env;
+    use crate::test_utils::*;
+    use super::*;
+
+    #[test]
+    fn remove_category_detaches_disabled_and_pending_apps() {
+        let mut contract = new_contract();
+        let games = add_category(&mut contract, "games");
+        let active_id = list_app(&mut contract, app_json("active", vec![games]), &author());
+        let disabled_id = list_app(&mut contract, app_json("disabled", vec![games]), &author());
+        set_predecessor(&guardian());
+        contract.disable_app(disabled_id);
+        contract.pending_apps.insert(&active_id, &PendingApp {
+            app: app_json("active", vec![games]),
+            submitted_by_account_id: author(),
+            deposit: WrappedBalance::from(0),
+            fee: WrappedBalance::from(0),
+            is_update: true,
+        });
+
+        contract.remove_category(games);
+
+        for app_id in [active_id, disabled_id] {
+            let app: App = contract.apps.get(&app_id).unwrap().into();
+            assert!(app.categories.is_empty());
+        }
+        assert!(contract.pending_apps.get(&active_id).unwrap().app.categories.is_empty());
+    }
+
+    // the counter, the active and disabled indexes and the apps themselves must agree
+    fn assert_active_apps(contract: &Contract, expected: u64) {
+        let active_apps = contract.apps.values().map(App::from).filter(|app| app.active.unwrap_or(true)).count() as u64;
+        assert_eq!(active_apps, expected);
+        assert_eq!(contract.active_apps, expected);
+        assert_eq!(contract.active_app_ids.len(), expected);
+        assert_eq!(contract.disabled_app_ids.len(), contract.apps.len() - expected);
+    }
+
+    #[test]
+    fn active_apps_follow_listing_lifecycle() {
+        let mut contract = new_contract();
+        let first_id = list_app(&mut contract, app_json("first", vec![]), &author());
+        let second_id = list_app(&mut contract, app_json("second", vec![]), &author());
+        assert_active_apps(&contract, 2);
+
+        set_predecessor(&guardian());
+        contract.disable_app(first_id);
+        assert_active_apps(&contract, 1);
+
+        // disabling twice must not count the app twice
+        set_predecessor(&guardian());
+        contract.disable_app(first_id);
+        assert_active_apps(&contract, 1);
+
+        set_predecessor(&guardian());
+        contract.enable_app(first_id);
+        assert_active_apps(&contract, 2);
+
+        set_predecessor(&guardian());
+        contract.disable_app(first_id);
+        set_predecessor(&guardian());
+        contract.enable_app(first_id);
+        assert_active_apps(&contract, 2);
+
+        set_predecessor(&author());
+        contract.delete_app(second_id);
+        assert_active_apps(&contract, 1);
+
+        // a deleted disabled app leaves both indexes
+        set_predecessor(&guardian());
+        contract.disable_app(first_id);
+        set_predecessor(&author());
+        contract.delete_app(first_id);
+        assert_active_apps(&contract, 0);
+        assert!(contract.disabled_app_ids.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_APP_IS_ACTIVE")]
+    fn enabling_active_app_fails() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("first", vec![]), &author());
+        set_predecessor(&guardian());
+        contract.enable_app(app_id);
+    }
+
+    #[test]
+    fn recount_apps_keeps_changes_made_during_the_walk() {
+        let mut contract = new_contract();
+        for index in 0..5 {
+            list_app(&mut contract, app_json(&format!("app{}", index), vec![]), &author());
+        }
+        contract.active_apps = 100;
+
+        set_predecessor(&guardian());
+        assert_eq!(contract.recount_apps(0, 2), Some(2));
+        // changes behind the cursor and ahead of it
+        contract.disable_app(0);
+        contract.disable_app(4);
+        set_predecessor(&author());
+        contract.delete_app(1);
+        set_predecessor(&guardian());
+        assert_eq!(contract.recount_apps(2, 10), None);
+        assert_active_apps(&contract, 2);
+
+        assert_eq!(contract.recount_apps(0, u64::MAX), None);
+        assert_active_apps(&contract, 2);
+    }
+
+    fn rename_app(contract: &mut Contract, app_id: AppId, slug: &str, new_slug: &str) {
+        let mut app = app_json(slug, vec![]);
+        app.slug = new_slug.to_string();
+        set_predecessor(&guardian());
+        contract.update_app(app_id, app);
+    }
+
+    #[test]
+    fn renamed_slug_stays_reserved_for_author() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("first", vec![]), &author());
+        rename_app(&mut contract, app_id, "first", "renamed");
+
+        let tombstone = contract.get_slug_tombstone("first".to_string()).unwrap();
+        assert_eq!(tombstone.account_id, author());
+        contract.assert_slug_available(&"first".to_string(), &author(), None);
+
+        // renaming back reuses the slug and releases the tombstone
+        rename_app(&mut contract, app_id, "first", "first");
+        assert!(contract.get_slug_tombstone("first".to_string()).is_none());
+        assert!(contract.get_slug_tombstone("renamed".to_string()).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_SLUG_IS_RESERVED")]
+    fn renamed_slug_is_reserved_for_others() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("first", vec![]), &author());
+        rename_app(&mut contract, app_id, "first", "renamed");
+        contract.assert_slug_available(&"first".to_string(), &stranger(), None);
+    }
+
+    #[test]
+    fn slug_tombstones_are_removed_when_expired_or_by_moderator() {
+        let mut contract = new_contract();
+        contract.internal_add_slug_tombstone(&"first".to_string(), author());
+        contract.internal_add_slug_tombstone(&"second".to_string(), author());
+
+        set_predecessor(&guardian());
+        contract.remove_slug_tombstone("first".to_string());
+        assert!(contract.get_slug_tombstone("first".to_string()).is_none());
+
+        testing_env!(context(&stranger()).block_timestamp(SLUG_TOMBSTONE_PERIOD).build());
+        contract.remove_slug_tombstone("second".to_string());
+        assert!(contract.get_slug_tombstone("second".to_string()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACCESS")]
+    fn active_slug_tombstone_is_kept_from_strangers() {
+        let mut contract = new_contract();
+        contract.internal_add_slug_tombstone(&"first".to_string(), author());
+        set_predecessor(&stranger());
+        contract.remove_slug_tombstone("first".to_string());
+    }
+
+    const UPDATE_DEPOSIT: Balance = 1_000_000_000_000_000_000_000_000;
+
+    fn update_title(contract: &mut Contract, account_id: &AccountId, deposit: Balance, added_by_account_id: Option<AccountId>) -> App {
+        let mut app = app_json("first", vec![]);
+        app.title = format!("Updated by {}", account_id);
+        app.added_by_account_id = added_by_account_id;
+        testing_env!(context(account_id).attached_deposit(deposit).build());
+        contract.update_app(0, app);
+        contract.apps.get(&0).unwrap().into()
+    }
+
+    fn listed_contract() -> Contract {
+        let mut contract = new_contract();
+        list_app(&mut contract, app_json("first", vec![]), &author());
+        contract
+    }
+
+    #[test]
+    fn author_updates_app_with_fee() {
+        let mut contract = listed_contract();
+        let app = update_title(&mut contract, &author(), UPDATE_DEPOSIT, None);
+        assert_eq!(app.title, "Updated by bob");
+        assert_eq!(contract.treasury, contract.update_fee);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_UPDATE_FEE_REQUIRED")]
+    fn author_update_requires_fee() {
+        let mut contract = listed_contract();
+        update_title(&mut contract, &author(), 0, None);
+    }
+
+    #[test]
+    fn maintainer_updates_app() {
+        let mut contract = listed_contract();
+        set_predecessor(&author());
+        contract.add_app_maintainer(0, stranger());
+        let app = update_title(&mut contract, &stranger(), UPDATE_DEPOSIT, None);
+        assert_eq!(app.title, "Updated by charlie");
+        assert_eq!(app.added_by_account_id, author());
+    }
+
+    #[test]
+    fn moderator_updates_app_for_free() {
+        let mut contract = listed_contract();
+        let moderator: AccountId = "moderator.near".parse().unwrap();
+        contract.internal_grant_role(&moderator, Role::Moderator, &guardian());
+        let app = update_title(&mut contract, &moderator, 0, None);
+        assert_eq!(app.title, "Updated by moderator.near");
+        assert_eq!(contract.treasury, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACCESS")]
+    fn stranger_cannot_update_app() {
+        let mut contract = listed_contract();
+        update_title(&mut contract, &stranger(), UPDATE_DEPOSIT, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACCESS")]
+    fn stranger_cannot_update_app_as_its_author() {
+        let mut contract = listed_contract();
+        update_title(&mut contract, &stranger(), UPDATE_DEPOSIT, Some(author()));
+    }
 
-        self.internal_insert_app(app_id, app, added_by_account_id, categories, contracts);
+    #[test]
+    fn spoofed_author_is_ignored_on_update() {
+        let mut contract = listed_contract();
+        let app = update_title(&mut contract, &author(), UPDATE_DEPOSIT, Some(stranger()));
+        assert_eq!(app.added_by_account_id, author());
+        assert_eq!(contract.internal_get_app_ids_by_author(&stranger(), None, None), Vec::<AppId>::new());
     }
-}
\ No newline at end of file
+}
@@ -15,6 +15,20 @@ mod category;
 mod web4;
 mod guardians;
 mod migration;
+mod search;
+mod events;
+mod moderation;
+mod fees;
+mod storage;
+mod ownership;
+mod governance;
+mod roles;
+mod upgrade;
+mod verification;
+mod recheck;
+mod metadata;
+#[cfg(test)]
+mod test_utils;
 
 type AppId = u64;
 type CategoryId = u64;
@@ -24,6 +38,16 @@ use crate::app::*;
 use crate::category::*;
 use crate::web4::*;
 use crate::utils::*;
+use crate::events::*;
+use crate::moderation::*;
+use crate::fees::*;
+use crate::storage::*;
+use crate::search::*;
+use crate::governance::*;
+use crate::roles::*;
+use crate::migration::*;
+use crate::recheck::*;
+use crate::metadata::*;
 
 #[derive(BorshSerialize, BorshStorageKey)]
 enum StorageKey {
@@ -38,6 +62,30 @@ enum StorageKey {
     AppIdsByAccountId,
 
     Guardians,
+
+    AppIdsBySearchToken,
+    AppIdsSetInSearchToken { token: String },
+
+    CategoryIdBySlug,
+    CategoryIdByTitle,
+
+    PendingApps,
+    AppStorageDeposits,
+    SlugTombstones,
+    PendingAppOwners,
+    MaintainersByAppId,
+    AppMaintainers { app_id: AppId },
+    AppIdsByMaintainer,
+    AppIdsSetByMaintainer { account_id: AccountId },
+    AppIdsByAuthor,
+    AppIdsSetByAuthor { account_id: AccountId },
+    Proposals,
+    RolesByAccountId,
+    AppIdByVerifiedContract,
+    VerifiedAppOwners,
+    Web4MetadataByAppId,
+    ActiveAppIds,
+    DisabledAppIds,
 }
 
 
@@ -52,10 +100,41 @@ pub struct Contract {
     app_id_by_slug: UnorderedMap<Slug, AppId>,
     apps_ids_by_category_id: UnorderedMap<CategoryId, UnorderedSet<AppId>>,
     app_id_by_dapp_account_id: UnorderedMap<AccountId, AppId>,
+    app_ids_by_search_token: UnorderedMap<String, UnorderedSet<AppId>>,
+    category_id_by_slug: UnorderedMap<Slug, CategoryId>,
+    category_id_by_title: UnorderedMap<String, CategoryId>,
 
     next_app_id: AppId,
     next_category_id: CategoryId,
-    disabled_apps: u64
+    active_apps: u64,
+    // app id a running recount has reached and the active apps it counted below that id
+    recounted_active_apps: Option<(AppId, u64)>,
+
+    pending_apps: UnorderedMap<AppId, PendingApp>,
+    moderation_enabled: bool,
+    rejection_fee_policy: RejectionFeePolicy,
+
+    listing_fee: Balance,
+    update_fee: Balance,
+    treasury: Balance,
+    app_storage_deposits: UnorderedMap<AppId, Balance>,
+    slug_tombstones: UnorderedMap<Slug, SlugTombstone>,
+    pending_app_owners: UnorderedMap<AppId, AccountId>,
+    maintainers_by_app_id: UnorderedMap<AppId, UnorderedSet<AccountId>>,
+    app_ids_by_maintainer: UnorderedMap<AccountId, UnorderedSet<AppId>>,
+    app_ids_by_author: UnorderedMap<AccountId, UnorderedSet<AppId>>,
+
+    proposals: UnorderedMap<ProposalId, Proposal>,
+    next_proposal_id: ProposalId,
+    approval_threshold: Option<u64>,
+    proposal_period: u64,
+    roles_by_account_id: UnorderedMap<AccountId, Vec<Role>>,
+    app_id_by_verified_contract: UnorderedMap<AccountId, AppId>,
+    verified_app_owners: UnorderedMap<AppId, AccountId>,
+    web4_metadata_by_app_id: UnorderedMap<AppId, Web4Metadata>,
+    active_app_ids: UnorderedSet<AppId>,
+    disabled_app_ids: UnorderedSet<AppId>,
+    state_version: u32
 }
 
 #[near_bindgen]
@@ -73,10 +152,40 @@ impl Contract {
             app_id_by_slug: UnorderedMap::new(StorageKey::AppIdBySlug),
             apps_ids_by_category_id: UnorderedMap::new(StorageKey::AppIdsByCategoryId),
             app_id_by_dapp_account_id: UnorderedMap::new(StorageKey::AppIdsByAccountId),
+            app_ids_by_search_token: UnorderedMap::new(StorageKey::AppIdsBySearchToken),
+            category_id_by_slug: UnorderedMap::new(StorageKey::CategoryIdBySlug),
+            category_id_by_title: UnorderedMap::new(StorageKey::CategoryIdByTitle),
 
             next_app_id: 0,
             next_category_id: 0,
-            disabled_apps: 0
+            active_apps: 0,
+            recounted_active_apps: None,
+
+            pending_apps: UnorderedMap::new(StorageKey::PendingApps),
+            moderation_enabled: false,
+            rejection_fee_policy: RejectionFeePolicy::Refund,
+
+            listing_fee: DEFAULT_LISTING_FEE,
+            update_fee: DEFAULT_UPDATE_FEE,
+            treasury: 0,
+            app_storage_deposits: UnorderedMap::new(StorageKey::AppStorageDeposits),
+            slug_tombstones: UnorderedMap::new(StorageKey::SlugTombstones),
+            pending_app_owners: UnorderedMap::new(StorageKey::PendingAppOwners),
+            maintainers_by_app_id: UnorderedMap::new(StorageKey::MaintainersByAppId),
+            app_ids_by_maintainer: UnorderedMap::new(StorageKey::AppIdsByMaintainer),
+            app_ids_by_author: UnorderedMap::new(StorageKey::AppIdsByAuthor),
+
+            proposals: UnorderedMap::new(StorageKey::Proposals),
+            next_proposal_id: 0,
+            approval_threshold: None,
+            proposal_period: DEFAULT_PROPOSAL_PERIOD,
+            roles_by_account_id: UnorderedMap::new(StorageKey::RolesByAccountId),
+            app_id_by_verified_contract: UnorderedMap::new(StorageKey::AppIdByVerifiedContract),
+            verified_app_owners: UnorderedMap::new(StorageKey::VerifiedAppOwners),
+            web4_metadata_by_app_id: UnorderedMap::new(StorageKey::Web4MetadataByAppId),
+            active_app_ids: UnorderedSet::new(StorageKey::ActiveAppIds),
+            disabled_app_ids: UnorderedSet::new(StorageKey::DisabledAppIds),
+            state_version: STATE_VERSION
         }
     }
 
@@ -92,14 +201,38 @@ impl Contract {
         unordered_map_pagination(&self.categories, from_index, limit)
     }
 
+    pub fn get_category_tree(&self) -> Vec<CategoryTreeJSON> {
+        build_category_tree(&mut self.internal_get_categories_by_parent(true), None)
+    }
+
+    pub fn get_category_by_slug(&self, slug: String) -> (CategoryId, CategoryJSON) {
+        let category_id = self.category_id_by_slug.get(&slug).expect("ERR_NO_SLUG");
+        (category_id, self.categories.get(&category_id).expect("ERR_NO_CATEGORY").into())
+    }
+
+    pub fn get_active_apps_count(&self) -> u64 {
+        self.active_apps
+    }
+
     pub fn get_category_apps_count(&self, category_id: CategoryId) -> u64 {
-        self.apps_ids_by_category_id.get(&category_id).expect("ERR_NO_CATEGORY").len()
+        self.internal_get_category_tree_apps_count(category_id)
     }
 
     pub fn get_app_by_slug(&self, slug: String) -> AppJSON {
        self.internal_get_app_by_slug(&slug).1.into()
     }
 
+    pub fn get_apps_by_author(&self, account_id: AccountId, from_index: Option<u64>, limit: Option<u64>) -> Vec<(AppId, AppJSON)> {
+        self.internal_get_app_ids_by_author(&account_id, from_index, limit)
+            .into_iter()
+            .map(|app_id| (app_id, self.apps.get(&app_id).expect("ERR_NO_APP").into()))
+            .collect()
+    }
+
+    pub fn get_slug_tombstone(&self, slug: String) -> Option<SlugTombstone> {
+        self.slug_tombstones.get(&slug)
+    }
+
     pub fn get_app_by_account_id(&self, account_id: AccountId) -> AppJSON {
         let app_id = self.app_id_by_dapp_account_id.get(&account_id).expect("ERR_ACCOUNT_ID");
         self.apps.get(&app_id).expect("ERR_NO_APP").into()
@@ -0,0 +1,238 @@
+use near_sdk::BlockHeight;
+use crate::*;
+
+// larger html bodies are not parsed to keep the callback within its gas
+pub const MAX_METADATA_BODY_LENGTH: usize = 8_192;
+const MAX_METADATA_TITLE_LENGTH: usize = 100;
+const MAX_METADATA_DESCRIPTION_LENGTH: usize = 250;
+const MAX_METADATA_URL_LENGTH: usize = 500;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Web4ResponseKind {
+    Body,
+    BodyUrl,
+    PreloadUrls,
+    Status,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Web4Metadata {
+    pub block_height: BlockHeight,
+    pub response_kind: Web4ResponseKind,
+    pub content_type: Option<String>,
+    pub body_url: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image_url: Option<String>,
+}
+
+impl Web4Metadata {
+    pub(crate) fn from_response(response: &Web4Response, dapp_account_id: &AccountId) -> Self {
+        let mut metadata = Web4Metadata {
+            block_height: env::block_height(),
+            response_kind: Web4ResponseKind::Status,
+            content_type: None,
+            body_url: None,
+            title: None,
+            description: None,
+            image_url: None,
+        };
+
+        match response {
+            Web4Response::Body { content_type, body } => {
+                metadata.response_kind = Web4ResponseKind::Body;
+                metadata.content_type = limit_text(filter_text(Some(content_type.clone())), MAX_METADATA_TITLE_LENGTH);
+                if content_type.to_ascii_lowercase().starts_with("text/html") && body.0.len() <= MAX_METADATA_BODY_LENGTH {
+                    let html = String::from_utf8_lossy(&body.0);
+                    let lowercase_html = html.to_ascii_lowercase();
+                    let meta_tags = html_meta_tags(&html, &lowercase_html);
+                    let meta_content = |key: &str| meta_tags.iter()
+                        .find(|(tag_key, _)| tag_key == key)
+                        .map(|(_, content)| content.clone());
+
+                    metadata.title = limit_text(filter_text(html_title(&html, &lowercase_html)), MAX_METADATA_TITLE_LENGTH);
+                    metadata.description = limit_text(filter_text(
+                        meta_content("description").or_else(|| meta_content("og:description"))
+                    ), MAX_METADATA_DESCRIPTION_LENGTH);
+                    metadata.image_url = limit_text(filter_text(
+                        meta_content("og:image").map(|url| absolute_url(url, dapp_account_id))
+                    ), MAX_METADATA_URL_LENGTH);
+                }
+            }
+            Web4Response::BodyUrl { body_url } => {
+                metadata.response_kind = Web4ResponseKind::BodyUrl;
+                metadata.body_url = limit_text(filter_text(Some(body_url.clone())), MAX_METADATA_URL_LENGTH);
+            }
+            Web4Response::PreloadUrls { .. } => {
+                metadata.response_kind = Web4ResponseKind::PreloadUrls;
+            }
+            Web4Response::Status { .. } => {}
+        }
+
+        metadata
+    }
+}
+
+impl App {
+    // listing data always wins, discovered metadata only fills the blanks
+    pub(crate) fn apply_web4_metadata(&mut self, metadata: Option<Web4Metadata>) {
+        if let Some(metadata) = metadata {
+            if self.title.is_empty() {
+                self.title = metadata.title.unwrap_or_default();
+            }
+            if self.oneliner.as_ref().map(|oneliner| oneliner.is_empty()).unwrap_or(true) {
+                self.oneliner = metadata.description;
+            }
+            if self.logo_url.as_ref().map(|logo_url| logo_url.is_empty()).unwrap_or(true) {
+                self.logo_url = metadata.image_url;
+            }
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_app_web4_metadata(&self, app_id: AppId) -> Option<Web4Metadata> {
+        self.web4_metadata_by_app_id.get(&app_id)
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_set_web4_metadata(&mut self, app_id: AppId, response: &Web4Response, dapp_account_id: &AccountId) {
+        self.web4_metadata_by_app_id.insert(&app_id, &Web4Metadata::from_response(response, dapp_account_id));
+    }
+}
+
+fn limit_text(s: Option<String>, max_length: usize) -> Option<String> {
+    s.map(|s| s.trim().chars().take(max_length).collect::<String>()).filter(|s| !s.is_empty())
+}
+
+// ascii lowercase keeps byte offsets, so positions found in the lowercased copy are valid in the original
+fn html_title(html: &str, lowercase_html: &str) -> Option<String> {
+    let start = lowercase_html.find("<title")?;
+    let start = start + lowercase_html[start..].find('>')? + 1;
+    let end = start + lowercase_html[start..].find("</title")?;
+    Some(html[start..end].to_string())
+}
+
+// (lowercased name or property, content) of every <meta> tag, in one pass over the document
+fn html_meta_tags(html: &str, lowercase_html: &str) -> Vec<(String, String)> {
+    let mut meta_tags = vec![];
+    let mut position = 0;
+    while let Some(start) = lowercase_html[position..].find("<meta") {
+        let start = position + start;
+        let end = match lowercase_html[start..].find('>') {
+            Some(length) => start + length,
+            None => break,
+        };
+        let (tag, lowercase_tag) = (&html[start..end], &lowercase_html[start..end]);
+        let key = html_attribute(tag, lowercase_tag, "name").or_else(|| html_attribute(tag, lowercase_tag, "property"));
+        if let (Some(key), Some(content)) = (key, html_attribute(tag, lowercase_tag, "content")) {
+            meta_tags.push((key.to_ascii_lowercase(), content));
+        }
+        position = end;
+    }
+    meta_tags
+}
+
+// attribute names may follow any ascii whitespace, values may be quoted with either quote or unquoted
+fn html_attribute(tag: &str, lowercase_tag: &str, name: &str) -> Option<String> {
+    let pattern = format!("{}=", name);
+    let mut position = 0;
+    while let Some(start) = lowercase_tag[position..].find(&pattern) {
+        let start = position + start;
+        position = start + pattern.len();
+        if start == 0 || !lowercase_tag.as_bytes()[start - 1].is_ascii_whitespace() {
+            continue;
+        }
+        let value = &tag[position..];
+        return match value.chars().next()? {
+            quote @ ('"' | '\'') => value[1..].split(quote).next().map(|value| value.to_string()),
+            _ => value.split(|c: char| c.is_ascii_whitespace()).next().map(|value| value.trim_end_matches('/').to_string()),
+        };
+    }
+    None
+}
+
+// relative urls are served by the dapp's own web4 gateway, protocol-relative ones use https
+fn absolute_url(url: String, dapp_account_id: &AccountId) -> String {
+    if url.starts_with("https://") || url.starts_with("http://") {
+        url
+    } else if let Some(url) = url.strip_prefix("//") {
+        format!("https://{}", url)
+    } else {
+        format!("https://{}.page/{}", dapp_account_id, url.trim_start_matches('/'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::{testing_env, PromiseResult, RuntimeFeesConfig, VMConfig};
+    use crate::test_utils::*;
+    use super::*;
+
+    fn html_metadata(html: &str) -> Web4Metadata {
+        set_predecessor(&contract_account_id());
+        Web4Metadata::from_response(&Web4Response::html_response(html.to_string()), &"dapp.near".parse().unwrap())
+    }
+
+    #[test]
+    fn reads_title_description_and_image() {
+        let metadata = html_metadata(r#"<html><head><TITLE>My Dapp</TITLE>
+            <meta
+                name="description" content="Best dapp on NEAR">
+            <meta	property='og:image'	content='//cdn.example.com/logo.png' /></head></html>"#);
+
+        assert_eq!(metadata.response_kind, Web4ResponseKind::Body);
+        assert_eq!(metadata.title, Some("My Dapp".to_string()));
+        assert_eq!(metadata.description, Some("Best dapp on NEAR".to_string()));
+        assert_eq!(metadata.image_url, Some("https://cdn.example.com/logo.png".to_string()));
+    }
+
+    #[test]
+    fn resolves_relative_image_on_dapp_gateway() {
+        let metadata = html_metadata(r#"<meta property="og:image" content="/logo.png"><meta name="og:description" content="From og">"#);
+
+        assert_eq!(metadata.image_url, Some("https://dapp.near.page/logo.png".to_string()));
+        assert_eq!(metadata.description, Some("From og".to_string()));
+    }
+
+    #[test]
+    fn ignores_large_bodies() {
+        let metadata = html_metadata(&format!("<title>Dapp</title>{}", " ".repeat(MAX_METADATA_BODY_LENGTH)));
+
+        assert_eq!(metadata.response_kind, Web4ResponseKind::Body);
+        assert_eq!(metadata.title, None);
+    }
+
+    #[test]
+    fn records_body_url() {
+        set_predecessor(&contract_account_id());
+        let metadata = Web4Metadata::from_response(&Web4Response::body_url("ipfs://bafy/index.html".to_string()), &"dapp.near".parse().unwrap());
+
+        assert_eq!(metadata.response_kind, Web4ResponseKind::BodyUrl);
+        assert_eq!(metadata.body_url, Some("ipfs://bafy/index.html".to_string()));
+        assert_eq!(metadata.title, None);
+    }
+
+    #[test]
+    fn largest_parsed_page_fits_callback_gas() {
+        let mut contract = new_contract();
+        let meta_tags = "<meta name=\"keywords\" content=\"near web4 dapp\">".repeat(100);
+        let html = format!("<html><head>{}<title>Dapp</title></head><body>{}</body></html>", meta_tags, "x".repeat(MAX_METADATA_BODY_LENGTH));
+        let html = html[..MAX_METADATA_BODY_LENGTH].to_string();
+
+        testing_env!(
+            context(&contract_account_id()).prepaid_gas(GAS_FOR_ON_WEB4_GET_CHECK_PROMISE_RESULT).build(),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Successful(vec![])]
+        );
+        contract.after_web4_get(Ok(Web4Response::html_response(html)), app_json("dapp", vec![]), WrappedBalance::from(0), author(), false);
+
+        assert_eq!(contract.get_app_web4_metadata(0).and_then(|metadata| metadata.title), Some("Dapp".to_string()));
+    }
+}
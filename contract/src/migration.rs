@@ -1,28 +1,126 @@
 use crate::*;
-#[near_bindgen]
-impl Contract {
-    /*
-    #[init(ignore_state)]
-    #[allow(dead_code)]
-    #[private]
-    pub fn migrate_state() -> Self {
-        #[derive(BorshDeserialize)]
-        struct OldContract {
-            guardians: UnorderedSet<AccountId>,
 
-            apps: UnorderedMap<AppId, VApp>,
-            categories: UnorderedMap<CategoryId, VCategory>,
+// bump together with a new historical layout below and a VContract variant for it
+pub const STATE_VERSION: u32 = 2;
+
+// layout before disabled apps were counted
+#[derive(BorshDeserialize)]
+pub(crate) struct ContractV0 {
+    guardians: UnorderedSet<AccountId>,
+
+    apps: UnorderedMap<AppId, VApp>,
+    categories: UnorderedMap<CategoryId, VCategory>,
+
+    app_id_by_slug: UnorderedMap<Slug, AppId>,
+    apps_ids_by_category_id: UnorderedMap<CategoryId, UnorderedSet<AppId>>,
+    app_id_by_dapp_account_id: UnorderedMap<AccountId, AppId>,
+
+    next_app_id: AppId,
+    next_category_id: CategoryId,
+}
+
+// layout before state_version was stored
+#[derive(BorshDeserialize)]
+pub(crate) struct ContractV1 {
+    guardians: UnorderedSet<AccountId>,
+
+    apps: UnorderedMap<AppId, VApp>,
+    categories: UnorderedMap<CategoryId, VCategory>,
+
+    app_id_by_slug: UnorderedMap<Slug, AppId>,
+    apps_ids_by_category_id: UnorderedMap<CategoryId, UnorderedSet<AppId>>,
+    app_id_by_dapp_account_id: UnorderedMap<AccountId, AppId>,
+
+    next_app_id: AppId,
+    next_category_id: CategoryId,
+    disabled_apps: u64
+}
+
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum VContract {
+    V0(ContractV0),
+    V1(ContractV1),
+    Current(Contract),
+}
+
+impl VContract {
+    // borsh requires every byte to be consumed, so only the matching layout deserializes
+    pub(crate) fn read() -> Self {
+        let state = env::storage_read(b"STATE").expect("ERR_NO_STATE");
+        if let Ok(contract) = Contract::try_from_slice(&state) {
+            if contract.state_version == STATE_VERSION {
+                return VContract::Current(contract);
+            }
+        }
+        if let Ok(contract) = ContractV1::try_from_slice(&state) {
+            return VContract::V1(contract);
+        }
+        if let Ok(contract) = ContractV0::try_from_slice(&state) {
+            return VContract::V0(contract);
+        }
+        env::panic_str("ERR_UNKNOWN_STATE_LAYOUT")
+    }
 
-            app_id_by_slug: UnorderedMap<Slug, AppId>,
-            apps_ids_by_category_id: UnorderedMap<CategoryId, UnorderedSet<AppId>>,
-            app_id_by_dapp_account_id: UnorderedMap<AccountId, AppId>,
+    fn guardians(&self) -> &UnorderedSet<AccountId> {
+        match self {
+            VContract::V0(contract) => &contract.guardians,
+            VContract::V1(contract) => &contract.guardians,
+            VContract::Current(contract) => &contract.guardians,
+        }
+    }
+}
 
-            next_app_id: AppId,
-            next_category_id: CategoryId,
+impl From<VContract> for Contract {
+    fn from(v_contract: VContract) -> Self {
+        match v_contract {
+            VContract::V0(contract) => ContractV1::from(contract).into(),
+            VContract::V1(contract) => contract.into(),
+            VContract::Current(contract) => contract,
         }
+    }
+}
 
-        let old_contract: OldContract = env::state_read().expect("Old state doesn't exist");
+impl From<ContractV0> for ContractV1 {
+    fn from(contract: ContractV0) -> Self {
+        ContractV1 {
+            guardians: contract.guardians,
+            apps: contract.apps,
+            categories: contract.categories,
 
+            app_id_by_slug: contract.app_id_by_slug,
+            apps_ids_by_category_id: contract.apps_ids_by_category_id,
+            app_id_by_dapp_account_id: contract.app_id_by_dapp_account_id,
+
+            next_app_id: contract.next_app_id,
+            next_category_id: contract.next_category_id,
+            disabled_apps: 0
+        }
+    }
+}
+
+impl From<ContractV1> for Contract {
+    fn from(old_contract: ContractV1) -> Self {
+        let mut category_id_by_slug = UnorderedMap::new(StorageKey::CategoryIdBySlug);
+        let mut category_id_by_title = UnorderedMap::new(StorageKey::CategoryIdByTitle);
+        for (category_id, v_category) in old_contract.categories.iter() {
+            let category: Category = v_category.into();
+            category_id_by_slug.insert(&category.slug, &category_id);
+            category_id_by_title.insert(&category.title, &category_id);
+        }
+
+        // the catalog pages and category removal read these sets, so they are filled here.
+        // active_apps is recounted from them, the old disabled_apps counter could drift
+        let mut active_app_ids = UnorderedSet::new(StorageKey::ActiveAppIds);
+        let mut disabled_app_ids = UnorderedSet::new(StorageKey::DisabledAppIds);
+        for (app_id, v_app) in old_contract.apps.iter() {
+            if App::from(v_app).active.unwrap_or(true) {
+                active_app_ids.insert(&app_id);
+            } else {
+                disabled_app_ids.insert(&app_id);
+            }
+        }
+
+        // search and author indexes are too large for one receipt, a curator fills them with rebuild_app_indexes afterwards
         Self {
             guardians: old_contract.guardians,
             apps: old_contract.apps,
@@ -31,11 +129,112 @@ impl Contract {
             app_id_by_slug: old_contract.app_id_by_slug,
             apps_ids_by_category_id: old_contract.apps_ids_by_category_id,
             app_id_by_dapp_account_id: old_contract.app_id_by_dapp_account_id,
+            app_ids_by_search_token: UnorderedMap::new(StorageKey::AppIdsBySearchToken),
+            category_id_by_slug,
+            category_id_by_title,
 
             next_app_id: old_contract.next_app_id,
             next_category_id: old_contract.next_category_id,
-            disabled_apps: 0
+            active_apps: active_app_ids.len(),
+            recounted_active_apps: None,
+
+            pending_apps: UnorderedMap::new(StorageKey::PendingApps),
+            moderation_enabled: false,
+            rejection_fee_policy: RejectionFeePolicy::Refund,
+
+            listing_fee: DEFAULT_LISTING_FEE,
+            update_fee: DEFAULT_UPDATE_FEE,
+            treasury: 0,
+            app_storage_deposits: UnorderedMap::new(StorageKey::AppStorageDeposits),
+            slug_tombstones: UnorderedMap::new(StorageKey::SlugTombstones),
+            pending_app_owners: UnorderedMap::new(StorageKey::PendingAppOwners),
+            maintainers_by_app_id: UnorderedMap::new(StorageKey::MaintainersByAppId),
+            app_ids_by_maintainer: UnorderedMap::new(StorageKey::AppIdsByMaintainer),
+            app_ids_by_author: UnorderedMap::new(StorageKey::AppIdsByAuthor),
+
+            proposals: UnorderedMap::new(StorageKey::Proposals),
+            next_proposal_id: 0,
+            approval_threshold: None,
+            proposal_period: DEFAULT_PROPOSAL_PERIOD,
+            // existing guardians keep full access with the Owner role, other roles start empty
+            roles_by_account_id: UnorderedMap::new(StorageKey::RolesByAccountId),
+            // contracts declared before are unverified until they call verify_app_contract
+            app_id_by_verified_contract: UnorderedMap::new(StorageKey::AppIdByVerifiedContract),
+            verified_app_owners: UnorderedMap::new(StorageKey::VerifiedAppOwners),
+            web4_metadata_by_app_id: UnorderedMap::new(StorageKey::Web4MetadataByAppId),
+            active_app_ids,
+            disabled_app_ids,
+            state_version: STATE_VERSION
         }
     }
-     */
-}
\ No newline at end of file
+}
+
+#[near_bindgen]
+impl Contract {
+    // upgrades the state from any historical layout, callable by the contract itself or a guardian.
+    // after upgrading from a layout without search and author indexes, call rebuild_app_indexes in chunks
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let v_contract = VContract::read();
+        let predecessor_id = env::predecessor_account_id();
+        require!(predecessor_id == env::current_account_id() || v_contract.guardians().contains(&predecessor_id), "ERR_NO_ACCESS");
+        v_contract.into()
+    }
+
+    pub fn get_state_version(&self) -> u32 {
+        self.state_version
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::*;
+    use super::*;
+
+    // moves the collections of a current contract into the old layout, the indexes the migration creates start empty
+    fn to_v1(mut contract: Contract, disabled_apps: u64) -> ContractV1 {
+        contract.category_id_by_slug.clear();
+        contract.category_id_by_title.clear();
+        contract.app_ids_by_search_token.clear();
+        contract.app_ids_by_author.clear();
+        contract.active_app_ids.clear();
+        contract.disabled_app_ids.clear();
+        ContractV1 {
+            guardians: contract.guardians,
+            apps: contract.apps,
+            categories: contract.categories,
+
+            app_id_by_slug: contract.app_id_by_slug,
+            apps_ids_by_category_id: contract.apps_ids_by_category_id,
+            app_id_by_dapp_account_id: contract.app_id_by_dapp_account_id,
+
+            next_app_id: contract.next_app_id,
+            next_category_id: contract.next_category_id,
+            disabled_apps
+        }
+    }
+
+    #[test]
+    fn migration_fills_active_and_disabled_apps() {
+        let mut contract = new_contract();
+        let games = add_category(&mut contract, "games");
+        for slug in ["chess", "poker", "tool"] {
+            list_app(&mut contract, app_json(slug, vec![games]), &author());
+        }
+        set_predecessor(&author());
+        contract.delete_app(1);
+        set_predecessor(&guardian());
+        contract.disable_app(2);
+
+        // the old counter drifted, the migrated one is taken from the apps
+        let mut migrated: Contract = to_v1(contract, 5).into();
+        assert_eq!(migrated.get_active_apps_count(), 1);
+        assert_eq!(migrated.active_app_ids.to_vec(), vec![0]);
+        assert_eq!(migrated.disabled_app_ids.to_vec(), vec![2]);
+
+        // removing a category reaches disabled apps before the indexes are rebuilt
+        migrated.remove_category(games);
+        let tool: App = migrated.apps.get(&2).expect("ERR_NO_APP").into();
+        assert!(tool.categories.is_empty());
+    }
+}
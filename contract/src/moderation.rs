@@ -0,0 +1,219 @@
+use crate::*;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RejectionFeePolicy {
+    Refund,
+    Charge,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingApp {
+    pub app: AppJSON,
+    pub submitted_by_account_id: AccountId,
+    pub deposit: WrappedBalance,
+    pub fee: WrappedBalance,
+    pub is_update: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ModerationJSON {
+    pub moderation_enabled: bool,
+    pub rejection_fee_policy: RejectionFeePolicy,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_moderation(&self) -> ModerationJSON {
+        ModerationJSON {
+            moderation_enabled: self.moderation_enabled,
+            rejection_fee_policy: self.rejection_fee_policy,
+        }
+    }
+
+    pub fn get_pending_apps(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<(AppId, PendingApp)> {
+        unordered_map_pagination(&self.pending_apps, from_index, limit)
+    }
+
+    pub fn set_moderation(&mut self, moderation_enabled: bool, rejection_fee_policy: RejectionFeePolicy) {
+        self.assert_role(Role::Moderator);
+        self.moderation_enabled = moderation_enabled;
+        self.rejection_fee_policy = rejection_fee_policy;
+    }
+
+    pub fn approve_app(&mut self, app_id: AppId) {
+        self.assert_role(Role::Moderator);
+        let pending_app = self.pending_apps.remove(&app_id).expect("ERR_NO_PENDING_APP");
+        self.treasury += pending_app.fee.0;
+        let storage_deposit = pending_app.deposit.0 - pending_app.fee.0;
+        let storage_usage_before = env::storage_usage();
+
+        Event::AppApproved { app_id, approved_by_account_id: &env::predecessor_account_id() }.emit();
+
+        if pending_app.is_update {
+            let old_app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+            let author_id = old_app.added_by_account_id.clone();
+            let mut app = pending_app.app;
+            app.active = old_app.active;
+            self.internal_update_app(app_id, app, old_app);
+            self.internal_settle_app_storage(app_id, storage_usage_before, storage_deposit, &pending_app.submitted_by_account_id, &author_id, true);
+        } else {
+            // slug or account may have been taken while the app was waiting for review
+            self.assert_slug_available(&filter_slug(pending_app.app.slug.clone()).to_lowercase(), &pending_app.submitted_by_account_id, None);
+            require!(self.app_id_by_dapp_account_id.get(&pending_app.app.dapp_account_id).is_none(), "ERR_ACCOUNT_ID_ALREADY_EXISTS");
+            self.internal_add_app(app_id, pending_app.app, pending_app.submitted_by_account_id.clone());
+            self.internal_settle_app_storage(app_id, storage_usage_before, storage_deposit, &pending_app.submitted_by_account_id, &pending_app.submitted_by_account_id, true);
+        }
+    }
+
+    pub fn reject_app(&mut self, app_id: AppId, reason: String) {
+        self.assert_role(Role::Moderator);
+        let pending_app = self.pending_apps.remove(&app_id).expect("ERR_NO_PENDING_APP");
+        if !pending_app.is_update {
+            self.web4_metadata_by_app_id.remove(&app_id);
+        }
+
+        // storage part of the deposit is always returned
+        let fee = if self.rejection_fee_policy == RejectionFeePolicy::Charge { pending_app.fee.0 } else { 0 };
+        self.treasury += fee;
+        let refund = pending_app.deposit.0 - fee;
+        if refund > 0 {
+            Promise::new(pending_app.submitted_by_account_id.clone()).transfer(refund);
+            log!("Deposit reverted");
+        }
+
+        Event::AppRejected {
+            app_id,
+            submitted_by_account_id: &pending_app.submitted_by_account_id,
+            rejected_by_account_id: &env::predecessor_account_id(),
+            reason: &reason,
+        }.emit();
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_add_pending_app(&mut self, app_id: AppId, app: AppJSON, submitted_by_account_id: AccountId, deposit: WrappedBalance, fee: WrappedBalance, is_update: bool) {
+        require!(self.pending_apps.get(&app_id).is_none(), "ERR_APP_IS_PENDING_REVIEW");
+
+        Event::AppSubmitted { app_id, submitted_by_account_id: &submitted_by_account_id, is_update }.emit();
+
+        self.pending_apps.insert(&app_id, &PendingApp {
+            app,
+            submitted_by_account_id,
+            deposit,
+            fee,
+            is_update,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::testing_env;
+    use crate::test_utils::*;
+    use super::*;
+
+    const DEPOSIT: Balance = 1_000_000_000_000_000_000_000_000;
+
+    fn moderated_contract(rejection_fee_policy: RejectionFeePolicy) -> Contract {
+        let mut contract = new_contract();
+        contract.set_moderation(true, rejection_fee_policy);
+        contract
+    }
+
+    fn submit_update(contract: &mut Contract, app_id: AppId, title: &str) {
+        let mut app = app_json("dapp", vec![]);
+        app.title = title.to_string();
+        testing_env!(context(&author()).attached_deposit(DEPOSIT).build());
+        contract.update_app(app_id, app);
+    }
+
+    #[test]
+    fn new_listing_waits_for_review() {
+        let mut contract = moderated_contract(RejectionFeePolicy::Refund);
+        let app_id = list_app_with_deposit(&mut contract, app_json("dapp", vec![]), &author(), DEPOSIT);
+
+        let pending_app = contract.pending_apps.get(&app_id).unwrap();
+        assert!(!pending_app.is_update);
+        assert_eq!(pending_app.deposit.0, DEPOSIT);
+        assert_eq!(pending_app.fee.0, contract.listing_fee);
+        assert!(contract.apps.get(&app_id).is_none());
+        assert_eq!(contract.treasury, 0);
+    }
+
+    #[test]
+    fn approved_listing_keeps_storage_and_refunds_the_rest() {
+        let mut contract = moderated_contract(RejectionFeePolicy::Refund);
+        let app_id = list_app_with_deposit(&mut contract, app_json("dapp", vec![]), &author(), DEPOSIT);
+
+        set_predecessor(&guardian());
+        contract.approve_app(app_id);
+
+        assert!(contract.apps.get(&app_id).is_some());
+        assert_eq!(contract.treasury, contract.listing_fee);
+        let storage_deposit = contract.app_storage_deposits.get(&app_id).unwrap();
+        assert!(storage_deposit > 0);
+        assert_eq!(transfers(), vec![(author(), DEPOSIT - contract.listing_fee - storage_deposit)]);
+    }
+
+    #[test]
+    fn approved_edit_is_applied_and_settled() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+        set_predecessor(&guardian());
+        contract.set_moderation(true, RejectionFeePolicy::Refund);
+        let storage_deposit_before = contract.app_storage_deposits.get(&app_id).unwrap_or(0);
+
+        submit_update(&mut contract, app_id, "A much longer title of the dapp");
+        assert!(contract.pending_apps.get(&app_id).unwrap().is_update);
+        assert_eq!(App::from(contract.apps.get(&app_id).unwrap()).title, "App dapp");
+
+        set_predecessor(&guardian());
+        contract.approve_app(app_id);
+
+        assert_eq!(App::from(contract.apps.get(&app_id).unwrap()).title, "A much longer title of the dapp");
+        assert_eq!(contract.treasury, contract.update_fee);
+        let storage_paid = contract.app_storage_deposits.get(&app_id).unwrap() - storage_deposit_before;
+        assert!(storage_paid > 0);
+        assert_eq!(transfers(), vec![(author(), DEPOSIT - contract.update_fee - storage_paid)]);
+    }
+
+    #[test]
+    fn rejection_refunds_everything_under_refund_policy() {
+        let mut contract = moderated_contract(RejectionFeePolicy::Refund);
+        let app_id = list_app_with_deposit(&mut contract, app_json("dapp", vec![]), &author(), DEPOSIT);
+
+        set_predecessor(&guardian());
+        contract.reject_app(app_id, "spam".to_string());
+
+        assert!(contract.pending_apps.get(&app_id).is_none());
+        assert_eq!(contract.treasury, 0);
+        assert_eq!(transfers(), vec![(author(), DEPOSIT)]);
+    }
+
+    #[test]
+    fn rejection_keeps_the_fee_under_charge_policy() {
+        let mut contract = moderated_contract(RejectionFeePolicy::Charge);
+        let app_id = list_app_with_deposit(&mut contract, app_json("dapp", vec![]), &author(), DEPOSIT);
+
+        set_predecessor(&guardian());
+        contract.reject_app(app_id, "spam".to_string());
+
+        assert_eq!(contract.treasury, contract.listing_fee);
+        assert_eq!(transfers(), vec![(author(), DEPOSIT - contract.listing_fee)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_APP_IS_PENDING_REVIEW")]
+    fn second_submission_waits_for_the_first() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+        set_predecessor(&guardian());
+        contract.set_moderation(true, RejectionFeePolicy::Refund);
+
+        submit_update(&mut contract, app_id, "First");
+        submit_update(&mut contract, app_id, "Second");
+    }
+}
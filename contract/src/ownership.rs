@@ -0,0 +1,200 @@
+use crate::*;
+
+const MAX_APP_MAINTAINERS: u64 = 10;
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_pending_app_owner(&self, app_id: AppId) -> Option<AccountId> {
+        self.pending_app_owners.get(&app_id)
+    }
+
+    // first step of the transfer, new owner has to call accept_app_ownership
+    pub fn transfer_app_ownership(&mut self, app_id: AppId, new_owner_id: AccountId) {
+        let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+        self.assert_app_owner(&app);
+        require!(app.added_by_account_id != new_owner_id, "ERR_ALREADY_OWNER");
+
+        self.pending_app_owners.insert(&app_id, &new_owner_id);
+
+        Event::AppOwnershipTransferStarted { app_id, owner_id: &app.added_by_account_id, new_owner_id: &new_owner_id }.emit();
+    }
+
+    pub fn cancel_app_ownership_transfer(&mut self, app_id: AppId) {
+        let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+        self.assert_app_owner(&app);
+        self.pending_app_owners.remove(&app_id).expect("ERR_NO_PENDING_OWNER");
+    }
+
+    pub fn accept_app_ownership(&mut self, app_id: AppId) {
+        let new_owner_id = self.pending_app_owners.get(&app_id).expect("ERR_NO_PENDING_OWNER");
+        require!(new_owner_id == env::predecessor_account_id(), "ERR_NO_ACCESS");
+        self.pending_app_owners.remove(&app_id);
+
+        // owners are never maintainers of their own apps
+        if self.is_app_maintainer(app_id, &new_owner_id) {
+            self.internal_remove_app_maintainer(app_id, &new_owner_id);
+            Event::AppMaintainerRemoved { app_id, account_id: &new_owner_id }.emit();
+        }
+
+        let mut app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+        let old_owner_id = std::mem::replace(&mut app.added_by_account_id, new_owner_id);
+        self.internal_remove_app_from_author(app_id, &old_owner_id);
+        self.internal_add_app_to_author(app_id, &app.added_by_account_id);
+
+        Event::AppOwnershipTransferred { app_id, old_owner_id: &old_owner_id, new_owner_id: &app.added_by_account_id }.emit();
+
+        self.apps.insert(&app_id, &VApp::Current(app));
+    }
+
+    pub fn get_app_maintainers(&self, app_id: AppId) -> Vec<AccountId> {
+        self.maintainers_by_app_id.get(&app_id).map(|maintainers| maintainers.to_vec()).unwrap_or_default()
+    }
+
+    pub fn get_apps_by_maintainer(&self, account_id: AccountId) -> Vec<(AppId, AppJSON)> {
+        self.app_ids_by_maintainer.get(&account_id)
+            .map(|app_ids| app_ids.to_vec())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|app_id| (app_id, self.apps.get(&app_id).expect("ERR_NO_APP").into()))
+            .collect()
+    }
+
+    pub fn add_app_maintainer(&mut self, app_id: AppId, account_id: AccountId) {
+        let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+        self.assert_app_owner(&app);
+        require!(app.added_by_account_id != account_id, "ERR_ALREADY_OWNER");
+
+        let mut maintainers = self.maintainers_by_app_id.get(&app_id).unwrap_or_else(||
+            UnorderedSet::new(StorageKey::AppMaintainers { app_id }));
+        require!(maintainers.len() < MAX_APP_MAINTAINERS, "ERR_TOO_MANY_MAINTAINERS");
+        require!(maintainers.insert(&account_id), "ERR_ALREADY_MAINTAINER");
+        self.maintainers_by_app_id.insert(&app_id, &maintainers);
+
+        let mut app_ids = self.app_ids_by_maintainer.get(&account_id).unwrap_or_else(||
+            UnorderedSet::new(StorageKey::AppIdsSetByMaintainer { account_id: account_id.clone() }));
+        app_ids.insert(&app_id);
+        self.app_ids_by_maintainer.insert(&account_id, &app_ids);
+
+        Event::AppMaintainerAdded { app_id, account_id: &account_id }.emit();
+    }
+
+    pub fn remove_app_maintainer(&mut self, app_id: AppId, account_id: AccountId) {
+        let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+        self.assert_app_owner(&app);
+        require!(self.is_app_maintainer(app_id, &account_id), "ERR_NOT_MAINTAINER");
+
+        self.internal_remove_app_maintainer(app_id, &account_id);
+
+        Event::AppMaintainerRemoved { app_id, account_id: &account_id }.emit();
+    }
+}
+
+impl Contract {
+    pub(crate) fn assert_app_owner(&self, app: &App) {
+        require!(app.added_by_account_id == env::predecessor_account_id(), "ERR_NO_ACCESS");
+    }
+
+    pub(crate) fn is_app_maintainer(&self, app_id: AppId, account_id: &AccountId) -> bool {
+        self.maintainers_by_app_id.get(&app_id).map(|maintainers| maintainers.contains(account_id)).unwrap_or(false)
+    }
+
+    pub(crate) fn internal_remove_app_maintainer(&mut self, app_id: AppId, account_id: &AccountId) {
+        if let Some(mut maintainers) = self.maintainers_by_app_id.get(&app_id) {
+            maintainers.remove(account_id);
+            if maintainers.is_empty() {
+                self.maintainers_by_app_id.remove(&app_id);
+            } else {
+                self.maintainers_by_app_id.insert(&app_id, &maintainers);
+            }
+        }
+
+        if let Some(mut app_ids) = self.app_ids_by_maintainer.get(account_id) {
+            app_ids.remove(&app_id);
+            if app_ids.is_empty() {
+                self.app_ids_by_maintainer.remove(account_id);
+            } else {
+                self.app_ids_by_maintainer.insert(account_id, &app_ids);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::*;
+    use super::*;
+
+    fn transfer(contract: &mut Contract, app_id: AppId, owner_id: &AccountId, new_owner_id: &AccountId) {
+        set_predecessor(owner_id);
+        contract.transfer_app_ownership(app_id, new_owner_id.clone());
+        set_predecessor(new_owner_id);
+        contract.accept_app_ownership(app_id);
+    }
+
+    #[test]
+    fn accepted_transfer_moves_author_index() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+        transfer(&mut contract, app_id, &author(), &stranger());
+
+        assert_eq!(App::from(contract.apps.get(&app_id).unwrap()).added_by_account_id, stranger());
+        assert!(contract.internal_get_app_ids_by_author(&author(), None, None).is_empty());
+        assert_eq!(contract.internal_get_app_ids_by_author(&stranger(), None, None), vec![app_id]);
+        assert!(contract.get_pending_app_owner(app_id).is_none());
+    }
+
+    #[test]
+    fn new_owner_stops_being_maintainer() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+        set_predecessor(&author());
+        contract.add_app_maintainer(app_id, stranger());
+        transfer(&mut contract, app_id, &author(), &stranger());
+
+        assert!(!contract.is_app_maintainer(app_id, &stranger()));
+        assert!(contract.get_apps_by_maintainer(stranger()).is_empty());
+    }
+
+    #[test]
+    fn owner_verification_resets_after_transfer() {
+        let mut contract = new_contract();
+        let dapp_account_id: AccountId = "dapp.near".parse().unwrap();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &dapp_account_id);
+        assert!(contract.is_app_owner_verified(app_id));
+
+        transfer(&mut contract, app_id, &dapp_account_id, &author());
+        assert!(!contract.is_app_owner_verified(app_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACCESS")]
+    fn non_owner_cannot_start_transfer() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+        set_predecessor(&stranger());
+        contract.transfer_app_ownership(app_id, stranger());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACCESS")]
+    fn stranger_cannot_accept_transfer() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+        set_predecessor(&author());
+        contract.transfer_app_ownership(app_id, guardian());
+        set_predecessor(&stranger());
+        contract.accept_app_ownership(app_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_PENDING_OWNER")]
+    fn cancelled_transfer_cannot_be_accepted() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+        set_predecessor(&author());
+        contract.transfer_app_ownership(app_id, stranger());
+        contract.cancel_app_ownership_transfer(app_id);
+        set_predecessor(&stranger());
+        contract.accept_app_ownership(app_id);
+    }
+}
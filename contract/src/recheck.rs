@@ -0,0 +1,180 @@
+use near_sdk::{is_promise_success, BlockHeight};
+use crate::*;
+
+// app is marked as broken after this many failed checks in a row
+pub const BROKEN_APP_FAILED_CHECKS: u32 = 3;
+// about a day of blocks between checks of the same app
+pub const RECHECK_INTERVAL_BLOCKS: BlockHeight = 86_400;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Web4Check {
+    pub block_height: BlockHeight,
+    pub success: bool,
+    pub failed_checks: u32,
+}
+
+impl Web4Check {
+    pub(crate) fn is_broken(&self) -> bool {
+        self.failed_checks >= BROKEN_APP_FAILED_CHECKS
+    }
+}
+
+impl App {
+    pub(crate) fn is_broken(&self) -> bool {
+        self.web4_check.as_ref().map(|web4_check| web4_check.is_broken()).unwrap_or(false)
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_app_web4_check(&self, app_id: AppId) -> Option<Web4Check> {
+        let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+        app.web4_check
+    }
+
+    // anyone may re-run the web4_get probe of the dapp, once per RECHECK_INTERVAL_BLOCKS.
+    // the interval also counts from the request, so a probe still in flight can't be repeated
+    pub fn recheck_app(&mut self, app_id: AppId) {
+        let mut app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+        let last_block_height = app.web4_check_requested_block_height
+            .max(app.web4_check.as_ref().map(|web4_check| web4_check.block_height));
+        if let Some(last_block_height) = last_block_height {
+            require!(env::block_height() >= last_block_height + RECHECK_INTERVAL_BLOCKS, "ERR_APP_WAS_CHECKED_RECENTLY");
+        }
+
+        let dapp_account_id = app.dapp_account_id.clone();
+        app.web4_check_requested_block_height = Some(env::block_height());
+        self.apps.insert(&app_id, &VApp::Current(app));
+
+        self.internal_recheck_app(app_id, dapp_account_id, false);
+    }
+
+    #[private]
+    pub fn after_recheck_web4_get(
+        &mut self,
+        #[callback_result] response: Result<Web4Response, PromiseError>,
+        app_id: AppId,
+        ignore_promise_success: bool
+    ) {
+        // app could be deleted while the dapp was queried
+        let mut app: App = if let Some(v_app) = self.apps.get(&app_id) { v_app.into() } else { return; };
+
+        if !(ignore_promise_success || is_promise_success()) {
+            log!("Promise failed. Sending request with data");
+            self.internal_recheck_app(app_id, app.dapp_account_id, true);
+            return;
+        }
+
+        // discovered metadata is kept as listed, recheck_app has no deposit to pay for rewriting it
+        let success = response.is_ok();
+        let failed_checks = if success {
+            0
+        } else {
+            app.web4_check.as_ref().map(|web4_check| web4_check.failed_checks).unwrap_or(0) + 1
+        };
+        let web4_check = Web4Check {
+            block_height: env::block_height(),
+            success,
+            failed_checks,
+        };
+
+        Event::AppChecked { app_id, success, failed_checks, broken: web4_check.is_broken() }.emit();
+
+        app.web4_check = Some(web4_check);
+        self.apps.insert(&app_id, &VApp::Current(app));
+    }
+}
+
+impl Contract {
+    fn internal_recheck_app(&self, app_id: AppId, dapp_account_id: AccountId, send_request_with_data: bool) {
+        let (web4_get, callback_gas, ignore_promise_success) = web4_get_probe(dapp_account_id, send_request_with_data);
+
+        web4_get
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(callback_gas)
+                    .after_recheck_web4_get(
+                        app_id,
+                        ignore_promise_success
+                    )
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::test_utils::get_logs;
+    use near_sdk::testing_env;
+    use crate::test_utils::*;
+    use super::*;
+
+    const LISTED_AT: BlockHeight = 1_000;
+
+    fn recheck_at(contract: &mut Contract, app_id: AppId, block_height: BlockHeight) {
+        testing_env!(context(&stranger()).block_index(block_height).build());
+        contract.recheck_app(app_id);
+    }
+
+    fn recheck_result(contract: &mut Contract, app_id: AppId, response: Result<Web4Response, PromiseError>) -> Web4Check {
+        set_predecessor(&contract_account_id());
+        contract.after_recheck_web4_get(response, app_id, true);
+        contract.get_app_web4_check(app_id).expect("ERR_NO_CHECK")
+    }
+
+    #[test]
+    fn failed_checks_accumulate_until_the_app_is_broken() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+
+        for failed_checks in 1..BROKEN_APP_FAILED_CHECKS {
+            let web4_check = recheck_result(&mut contract, app_id, Err(PromiseError::Failed));
+            assert_eq!(web4_check.failed_checks, failed_checks);
+            assert!(!web4_check.success);
+            assert!(!web4_check.is_broken());
+        }
+        let web4_check = recheck_result(&mut contract, app_id, Err(PromiseError::Failed));
+        assert!(web4_check.is_broken());
+        assert!(App::from(contract.apps.get(&app_id).unwrap()).is_broken());
+
+        let web4_check = recheck_result(&mut contract, app_id, Ok(dapp_response()));
+        assert!(web4_check.success);
+        assert_eq!(web4_check.failed_checks, 0);
+        assert!(!web4_check.is_broken());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_APP_WAS_CHECKED_RECENTLY")]
+    fn recheck_in_flight_is_not_repeated() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+        recheck_at(&mut contract, app_id, LISTED_AT);
+        recheck_at(&mut contract, app_id, LISTED_AT + 1);
+    }
+
+    #[test]
+    fn recheck_is_allowed_after_the_interval() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+        recheck_at(&mut contract, app_id, LISTED_AT);
+        assert_eq!(App::from(contract.apps.get(&app_id).unwrap()).web4_check_requested_block_height, Some(LISTED_AT));
+
+        recheck_at(&mut contract, app_id, LISTED_AT + RECHECK_INTERVAL_BLOCKS);
+        assert_eq!(App::from(contract.apps.get(&app_id).unwrap()).web4_check_requested_block_height,
+                   Some(LISTED_AT + RECHECK_INTERVAL_BLOCKS));
+    }
+
+    #[test]
+    fn recheck_of_a_deleted_app_is_dropped() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+        recheck_at(&mut contract, app_id, LISTED_AT);
+        set_predecessor(&author());
+        contract.delete_app(app_id);
+
+        set_predecessor(&contract_account_id());
+        contract.after_recheck_web4_get(Err(PromiseError::Failed), app_id, false);
+        assert!(contract.apps.get(&app_id).is_none());
+        assert!(!get_logs().iter().any(|log| log.contains("app_checked")));
+    }
+}
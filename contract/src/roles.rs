@@ -0,0 +1,125 @@
+use crate::*;
+
+// owners are the guardians, they have every other role and vote on proposals.
+// moderators review, disable and enable apps and set the moderation policy,
+// curators manage categories and repair the catalog counters and indexes, treasurers propose fee withdrawals.
+// proposals, votes and staging or upgrading the code stay owner-only
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Role {
+    Owner,
+    Moderator,
+    Curator,
+    Treasurer,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_roles(&self, account_id: AccountId) -> Vec<Role> {
+        let mut roles = self.roles_by_account_id.get(&account_id).unwrap_or_default();
+        if self.guardians.contains(&account_id) {
+            roles.insert(0, Role::Owner);
+        }
+        roles
+    }
+
+    pub fn get_role_members(&self, role: Role) -> Vec<AccountId> {
+        if role == Role::Owner {
+            return self.guardians.to_vec();
+        }
+        self.roles_by_account_id.iter()
+            .filter(|(_, roles)| roles.contains(&role))
+            .map(|(account_id, _)| account_id)
+            .collect()
+    }
+
+    pub fn grant_role(&mut self, account_id: AccountId, role: Role) -> ProposalId {
+        self.add_proposal(ProposalAction::GrantRole { account_id, role })
+    }
+
+    pub fn revoke_role(&mut self, account_id: AccountId, role: Role) -> ProposalId {
+        self.add_proposal(ProposalAction::RevokeRole { account_id, role })
+    }
+}
+
+impl Contract {
+    pub(crate) fn has_role(&self, account_id: &AccountId, role: Role) -> bool {
+        self.guardians.contains(account_id)
+            || self.roles_by_account_id.get(account_id).map(|roles| roles.contains(&role)).unwrap_or(false)
+    }
+
+    pub(crate) fn assert_role(&self, role: Role) {
+        require!(self.has_role(&env::predecessor_account_id(), role), "ERR_NO_ACCESS");
+    }
+
+    pub(crate) fn internal_grant_role(&mut self, account_id: &AccountId, role: Role, granted_by_account_id: &AccountId) {
+        if role == Role::Owner {
+            return self.internal_add_guardian(account_id, granted_by_account_id);
+        }
+
+        let mut roles = self.roles_by_account_id.get(account_id).unwrap_or_default();
+        if !roles.contains(&role) {
+            roles.push(role);
+            self.roles_by_account_id.insert(account_id, &roles);
+        }
+
+        Event::RoleGranted { account_id, role, granted_by_account_id }.emit();
+    }
+
+    pub(crate) fn internal_revoke_role(&mut self, account_id: &AccountId, role: Role, revoked_by_account_id: &AccountId) {
+        if role == Role::Owner {
+            return self.internal_remove_guardian(account_id, revoked_by_account_id);
+        }
+
+        let mut roles = self.roles_by_account_id.get(account_id).unwrap_or_default();
+        require!(roles.contains(&role), "ERR_NO_ROLE");
+        roles.retain(|account_role| account_role != &role);
+        if roles.is_empty() {
+            self.roles_by_account_id.remove(account_id);
+        } else {
+            self.roles_by_account_id.insert(account_id, &roles);
+        }
+
+        Event::RoleRevoked { account_id, role, revoked_by_account_id }.emit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::*;
+    use super::*;
+
+    #[test]
+    fn roles_grant_their_own_actions_only() {
+        let mut contract = new_contract();
+        let (moderator, curator): (AccountId, AccountId) = ("moderator.near".parse().unwrap(), "curator.near".parse().unwrap());
+        contract.internal_grant_role(&moderator, Role::Moderator, &guardian());
+        contract.internal_grant_role(&curator, Role::Curator, &guardian());
+
+        set_predecessor(&moderator);
+        contract.set_moderation(true, RejectionFeePolicy::Charge);
+        assert!(contract.get_moderation().moderation_enabled);
+
+        set_predecessor(&curator);
+        assert_eq!(contract.recount_apps(0, 10), None);
+        contract.rebuild_app_indexes(None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACCESS")]
+    fn curator_cannot_set_moderation() {
+        let mut contract = new_contract();
+        contract.internal_grant_role(&author(), Role::Curator, &guardian());
+        set_predecessor(&author());
+        contract.set_moderation(true, RejectionFeePolicy::Charge);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACCESS")]
+    fn moderator_cannot_rebuild_indexes() {
+        let mut contract = new_contract();
+        contract.internal_grant_role(&author(), Role::Moderator, &guardian());
+        set_predecessor(&author());
+        contract.rebuild_app_indexes(None, None);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::*;
+
+const MIN_SEARCH_TOKEN_LENGTH: usize = 2;
+const MAX_SEARCH_TOKEN_LENGTH: usize = 32;
+const MAX_SEARCH_QUERY_TOKENS: usize = 10;
+// every indexed token costs storage writes in the listing callback
+pub const MAX_APP_SEARCH_TOKENS: usize = 64;
+
+#[near_bindgen]
+impl Contract {
+    pub fn search_apps(&self, query: String, from_index: Option<u64>, limit: Option<u64>) -> Vec<(AppId, AppJSON)> {
+        self.internal_search_apps(&query, from_index, limit)
+            .into_iter()
+            .map(|app_id| (app_id, self.apps.get(&app_id).expect("ERR_NO_APP").into()))
+            .collect()
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_search_apps(&self, query: &str, from_index: Option<u64>, limit: Option<u64>) -> Vec<AppId> {
+        let tokens = search_tokens(query);
+        require!(tokens.len() <= MAX_SEARCH_QUERY_TOKENS, "ERR_QUERY_IS_TOO_LONG");
+        if tokens.is_empty() {
+            return vec![];
+        }
+
+        let mut sets = vec![];
+        for token in tokens {
+            if let Some(app_ids) = self.app_ids_by_search_token.get(&token) {
+                sets.push(app_ids);
+            } else {
+                return vec![];
+            }
+        }
+
+        // iterate over the smallest set and check the rest
+        sets.sort_by_key(|app_ids| app_ids.len());
+        let mut app_ids: Vec<AppId> = sets[0]
+            .iter()
+            .filter(|app_id| sets[1..].iter().all(|other| other.contains(app_id)))
+            .collect();
+        app_ids.sort_unstable();
+
+        let from_index = from_index.unwrap_or(0) as usize;
+        let limit = limit.map(|limit| limit as usize).unwrap_or(app_ids.len());
+        app_ids.into_iter().skip(from_index).take(limit).collect()
+    }
+
+    pub(crate) fn internal_add_app_to_search_index(&mut self, app_id: AppId, app: &App) {
+        for token in app.search_tokens() {
+            let mut app_ids = self.app_ids_by_search_token.get(&token).unwrap_or_else(||
+                UnorderedSet::new(StorageKey::AppIdsSetInSearchToken { token: token.clone() }));
+            app_ids.insert(&app_id);
+            self.app_ids_by_search_token.insert(&token, &app_ids);
+        }
+    }
+
+    pub(crate) fn internal_remove_app_from_search_index(&mut self, app_id: AppId, app: &App) {
+        for token in app.search_tokens() {
+            if let Some(mut app_ids) = self.app_ids_by_search_token.get(&token) {
+                app_ids.remove(&app_id);
+                if app_ids.is_empty() {
+                    self.app_ids_by_search_token.remove(&token);
+                } else {
+                    self.app_ids_by_search_token.insert(&token, &app_ids);
+                }
+            }
+        }
+    }
+}
+
+impl App {
+    // title tokens go first, so a long description can't push them out of the index
+    pub(crate) fn search_tokens(&self) -> Vec<String> {
+        let mut tokens: Vec<String> = vec![];
+        for text in [Some(self.title.clone()), self.oneliner.clone(), self.description.clone()].iter().flatten() {
+            for token in search_tokens(text) {
+                if tokens.len() == MAX_APP_SEARCH_TOKENS {
+                    return tokens;
+                }
+                if !tokens.contains(&token) {
+                    tokens.push(token);
+                }
+            }
+        }
+        tokens
+    }
+}
+
+pub(crate) fn search_tokens(text: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .map(|token| token.to_lowercase())
+        .filter(|token| {
+            let length = token.chars().count();
+            (MIN_SEARCH_TOKEN_LENGTH..=MAX_SEARCH_TOKEN_LENGTH).contains(&length)
+        })
+        .collect();
+    tokens.sort_unstable();
+    tokens.dedup();
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::*;
+    use super::*;
+
+    fn found(contract: &Contract, query: &str) -> Vec<AppId> {
+        contract.internal_search_apps(query, None, None)
+    }
+
+    #[test]
+    fn updated_texts_replace_indexed_tokens() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("chess", vec![]), &author());
+        assert_eq!(found(&contract, "chess"), vec![app_id]);
+
+        let mut app = app_json("chess", vec![]);
+        app.title = "Checkers".to_string();
+        app.oneliner = Some("Board game".to_string());
+        app.description = None;
+        set_predecessor(&guardian());
+        contract.update_app(app_id, app);
+
+        assert!(found(&contract, "oneliner").is_empty());
+        assert!(found(&contract, "description").is_empty());
+        assert_eq!(found(&contract, "checkers board"), vec![app_id]);
+        // tokens left without apps are dropped from the index
+        assert!(contract.app_ids_by_search_token.get(&"description".to_string()).is_none());
+        assert!(contract.app_ids_by_search_token.get(&"oneliner".to_string()).is_none());
+    }
+
+    #[test]
+    fn disabled_apps_are_not_found() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("chess", vec![]), &author());
+
+        set_predecessor(&guardian());
+        contract.disable_app(app_id);
+        assert!(found(&contract, "chess").is_empty());
+        assert_eq!(contract.app_ids_by_search_token.len(), 0);
+
+        set_predecessor(&guardian());
+        contract.enable_app(app_id);
+        assert_eq!(found(&contract, "chess").len(), 1);
+    }
+}
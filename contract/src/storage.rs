@@ -0,0 +1,164 @@
+use crate::*;
+use near_sdk::StorageUsage;
+
+// rough upper bounds used to check the deposit before the listing is written
+const STORAGE_BYTES_PER_APP: StorageUsage = 1_500;
+const STORAGE_BYTES_PER_SEARCH_TOKEN: StorageUsage = 200;
+const STORAGE_BYTES_PER_SET_ITEM: StorageUsage = 150;
+
+// storage allowance attached by the web4 submit form on top of the listing fee
+pub const SUBMIT_FORM_STORAGE_DEPOSIT: Balance = 500_000_000_000_000_000_000_000; // 0.5 NEAR
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_app_storage_deposit(&self, app_id: AppId) -> WrappedBalance {
+        WrappedBalance::from(self.app_storage_deposits.get(&app_id).unwrap_or(0))
+    }
+
+    pub fn get_listing_deposit(&self, app: AppJSON) -> WrappedBalance {
+        WrappedBalance::from(self.listing_fee + estimate_app_storage_cost(&app))
+    }
+}
+
+impl Contract {
+    // charges storage growth since storage_usage_before from the deposit, refunds the unused deposit to the payer
+    // and storage released by a smaller listing to the app author
+    pub(crate) fn internal_settle_app_storage(&mut self, app_id: AppId, storage_usage_before: StorageUsage, deposit: Balance,
+                                              payer_id: &AccountId, author_id: &AccountId, allow_shortfall: bool) {
+        let storage_usage_after = env::storage_usage();
+        let mut storage_deposit = self.app_storage_deposits.get(&app_id).unwrap_or(0);
+        let mut payer_refund = deposit;
+        let mut author_refund: Balance = 0;
+
+        if storage_usage_after > storage_usage_before {
+            let storage_cost = Balance::from(storage_usage_after - storage_usage_before) * env::storage_byte_cost();
+            if !allow_shortfall {
+                require!(deposit >= storage_cost, "ERR_NOT_ENOUGH_STORAGE_DEPOSIT");
+            } else if deposit < storage_cost {
+                log!("Storage shortfall of {} covered by contract", storage_cost - deposit);
+            }
+            let storage_paid = std::cmp::min(deposit, storage_cost);
+            storage_deposit += storage_paid;
+            payer_refund -= storage_paid;
+        } else {
+            let storage_released = Balance::from(storage_usage_before - storage_usage_after) * env::storage_byte_cost();
+            author_refund = std::cmp::min(storage_released, storage_deposit);
+            storage_deposit -= author_refund;
+        }
+
+        self.app_storage_deposits.insert(&app_id, &storage_deposit);
+
+        if payer_id == author_id {
+            payer_refund += author_refund;
+            author_refund = 0;
+        }
+        if payer_refund > 0 {
+            Promise::new(payer_id.clone()).transfer(payer_refund);
+        }
+        if author_refund > 0 {
+            Promise::new(author_id.clone()).transfer(author_refund);
+        }
+    }
+}
+
+pub(crate) fn estimate_app_storage_cost(app: &AppJSON) -> Balance {
+    let tokens = search_tokens(&format!("{} {} {}",
+                                        app.title,
+                                        app.oneliner.clone().unwrap_or_default(),
+                                        app.description.clone().unwrap_or_default()));
+    let items = app.categories.len() + app.contracts.clone().unwrap_or_default().len();
+    let bytes = STORAGE_BYTES_PER_APP
+        + app.try_to_vec().unwrap_or_default().len() as StorageUsage
+        + tokens.len().min(MAX_APP_SEARCH_TOKENS) as StorageUsage * STORAGE_BYTES_PER_SEARCH_TOKEN
+        + items as StorageUsage * STORAGE_BYTES_PER_SET_ITEM;
+    Balance::from(bytes) * env::storage_byte_cost()
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::testing_env;
+    use crate::test_utils::*;
+    use super::*;
+
+    const DEPOSIT: Balance = 1_000_000_000_000_000_000_000_000;
+
+    fn listed_app(contract: &mut Contract, deposit: Balance) -> AppId {
+        let mut app = app_json("dapp", vec![]);
+        app.description = Some("Long description of the dapp ".repeat(20));
+        list_app_with_deposit(contract, app, &author(), deposit)
+    }
+
+    fn update_description(contract: &mut Contract, app_id: AppId, account_id: &AccountId, deposit: Balance, description: String) {
+        let mut app = app_json("dapp", vec![]);
+        app.description = Some(description);
+        testing_env!(context(account_id).attached_deposit(deposit).build());
+        contract.update_app(app_id, app);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NOT_ENOUGH_STORAGE_DEPOSIT")]
+    fn author_update_must_pay_for_storage() {
+        let mut contract = new_contract();
+        let app_id = listed_app(&mut contract, 0);
+        let update_fee = contract.update_fee;
+        update_description(&mut contract, app_id, &author(), update_fee, "Even longer description ".repeat(40));
+    }
+
+    #[test]
+    fn shrinking_update_refunds_the_author() {
+        let mut contract = new_contract();
+        let app_id = listed_app(&mut contract, DEPOSIT);
+        let storage_deposit = contract.app_storage_deposits.get(&app_id).unwrap();
+
+        let update_fee = contract.update_fee;
+        update_description(&mut contract, app_id, &author(), update_fee, "Short".to_string());
+
+        let refund = storage_deposit - contract.app_storage_deposits.get(&app_id).unwrap();
+        assert!(refund > 0);
+        assert_eq!(transfers(), vec![(author(), refund)]);
+    }
+
+    #[test]
+    fn refund_is_limited_to_the_recorded_deposit() {
+        let mut contract = new_contract();
+        // listed without a deposit, the contract paid for the storage
+        let app_id = listed_app(&mut contract, 0);
+        assert_eq!(contract.app_storage_deposits.get(&app_id), Some(0));
+
+        let update_fee = contract.update_fee;
+        update_description(&mut contract, app_id, &author(), update_fee, "Short".to_string());
+
+        assert!(transfers().is_empty());
+        assert_eq!(contract.app_storage_deposits.get(&app_id), Some(0));
+    }
+
+    #[test]
+    fn guardian_edit_refunds_the_author() {
+        let mut contract = new_contract();
+        let app_id = listed_app(&mut contract, DEPOSIT);
+        let storage_deposit = contract.app_storage_deposits.get(&app_id).unwrap();
+
+        update_description(&mut contract, app_id, &guardian(), 0, "Short".to_string());
+
+        let refund = storage_deposit - contract.app_storage_deposits.get(&app_id).unwrap();
+        assert!(refund > 0);
+        assert_eq!(transfers(), vec![(author(), refund)]);
+    }
+
+    #[test]
+    fn deleted_app_returns_its_storage_deposit() {
+        let mut contract = new_contract();
+        let app_id = listed_app(&mut contract, DEPOSIT);
+        let storage_deposit = contract.app_storage_deposits.get(&app_id).unwrap();
+
+        set_predecessor(&author());
+        contract.delete_app(app_id);
+
+        // the slug tombstone stays in the state, so the refund covers the released bytes only
+        let refunds = transfers();
+        assert_eq!(refunds.len(), 1);
+        assert_eq!(refunds[0].0, author());
+        assert!(refunds[0].1 > 0 && refunds[0].1 <= storage_deposit);
+        assert!(contract.app_storage_deposits.get(&app_id).is_none());
+    }
+}
@@ -0,0 +1,99 @@
+// helpers shared by the unit tests of the contract modules
+use near_sdk::mock::VmAction;
+use near_sdk::test_utils::{accounts, get_created_receipts, VMContextBuilder};
+use near_sdk::testing_env;
+use crate::*;
+
+pub(crate) fn contract_account_id() -> AccountId {
+    "awesomeweb4.near".parse().unwrap()
+}
+
+pub(crate) fn guardian() -> AccountId {
+    accounts(0)
+}
+
+pub(crate) fn author() -> AccountId {
+    accounts(1)
+}
+
+pub(crate) fn stranger() -> AccountId {
+    accounts(2)
+}
+
+pub(crate) fn context(predecessor_id: &AccountId) -> VMContextBuilder {
+    let mut builder = VMContextBuilder::new();
+    builder
+        .current_account_id(contract_account_id())
+        .predecessor_account_id(predecessor_id.clone())
+        .signer_account_id(predecessor_id.clone());
+    builder
+}
+
+pub(crate) fn set_predecessor(predecessor_id: &AccountId) {
+    testing_env!(context(predecessor_id).build());
+}
+
+pub(crate) fn new_contract() -> Contract {
+    set_predecessor(&guardian());
+    Contract::new(guardian())
+}
+
+pub(crate) fn app_json(slug: &str, categories: Vec<CategoryId>) -> AppJSON {
+    AppJSON {
+        added_by_account_id: None,
+        dapp_account_id: format!("{}.near", slug).parse().unwrap(),
+        slug: slug.to_string(),
+        title: format!("App {}", slug),
+        categories: categories.iter().map(|category_id| category_id.to_string()).collect(),
+        oneliner: Some(format!("Oneliner of {}", slug)),
+        description: Some(format!("Description of {}", slug)),
+        logo_url: None,
+        twitter: None,
+        facebook: None,
+        medium: None,
+        telegram: None,
+        github: None,
+        discord: None,
+        symbol: None,
+        contracts: None,
+        token_address: None,
+        active: None,
+    }
+}
+
+pub(crate) fn dapp_response() -> Web4Response {
+    Web4Response::html_response("<html><head><title>Dapp</title></head><body></body></html>".to_string())
+}
+
+// lists the app through the web4_get callback, as if the dapp answered with dapp_response
+pub(crate) fn list_app(contract: &mut Contract, app: AppJSON, added_by_account_id: &AccountId) -> AppId {
+    list_app_with_deposit(contract, app, added_by_account_id, 0)
+}
+
+pub(crate) fn list_app_with_deposit(contract: &mut Contract, app: AppJSON, added_by_account_id: &AccountId, deposit: Balance) -> AppId {
+    set_predecessor(&contract_account_id());
+    let app_id = contract.next_app_id;
+    contract.after_web4_get(Ok(dapp_response()), app, WrappedBalance::from(deposit), added_by_account_id.clone(), true);
+    app_id
+}
+
+// (receiver, amount) of every transfer scheduled by the last call
+pub(crate) fn transfers() -> Vec<(AccountId, Balance)> {
+    get_created_receipts()
+        .into_iter()
+        .flat_map(|receipt| {
+            let receiver_id = receipt.receiver_id;
+            receipt.actions.into_iter().filter_map(move |action| match action {
+                VmAction::Transfer { deposit } => Some((receiver_id.clone(), deposit)),
+                _ => None,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn add_category(contract: &mut Contract, slug: &str) -> CategoryId {
+    set_predecessor(&guardian());
+    let category_id = contract.next_category_id;
+    contract.add_category(format!("Category {}", slug), slug.to_string(), None);
+    category_id
+}
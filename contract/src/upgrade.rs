@@ -0,0 +1,142 @@
+use crate::*;
+use near_sdk::json_types::Base58CryptoHash;
+
+// raw storage key, doesn't clash with the single byte StorageKey prefixes
+const STAGED_CODE_KEY: &[u8] = b"staged_code";
+
+pub const GAS_FOR_MIGRATE: Gas = Gas(Gas::ONE_TERA.0 * 100);
+pub const GAS_FOR_STATE_CHECK: Gas = Gas(Gas::ONE_TERA.0 * 10);
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VersionJSON {
+    pub version: String,
+    pub git_hash: String,
+    pub state_version: u32,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_version(&self) -> VersionJSON {
+        VersionJSON {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_hash: option_env!("GIT_HASH").unwrap_or("unknown").to_string(),
+            state_version: self.state_version,
+        }
+    }
+
+    pub fn get_staged_code_hash(&self) -> Option<Base58CryptoHash> {
+        env::storage_read(STAGED_CODE_KEY).map(|code| Base58CryptoHash::from(env::sha256_array(&code)))
+    }
+
+    // raw input is appended to the staged code, so the wasm may be uploaded in several transactions.
+    // staging stays owner-only like the upgrade itself
+    pub fn stage_code(&mut self) {
+        self.assert_guardian();
+        let chunk = env::input().expect("ERR_NO_INPUT");
+        require!(!chunk.is_empty(), "ERR_NO_INPUT");
+        let mut code = env::storage_read(STAGED_CODE_KEY).unwrap_or_default();
+        code.extend(chunk);
+        env::storage_write(STAGED_CODE_KEY, &code);
+    }
+
+    pub fn clear_staged_code(&mut self) {
+        self.assert_guardian();
+        env::storage_remove(STAGED_CODE_KEY);
+    }
+
+    // raw input replaces the staged code, empty input proposes the code staged with stage_code
+    pub fn upgrade(&mut self) -> ProposalId {
+        self.assert_guardian();
+        let input = env::input().unwrap_or_default();
+        if !input.is_empty() {
+            env::storage_write(STAGED_CODE_KEY, &input);
+        }
+        let code_hash = self.get_staged_code_hash().expect("ERR_NO_STAGED_CODE");
+        self.add_proposal(ProposalAction::Upgrade { code_hash })
+    }
+}
+
+impl Contract {
+    // deploy, migrate and state check are actions of one receipt, if any of them fails the old code stays.
+    // rebuild_app_indexes is not chained, it may need several chunks after upgrading an old layout
+    pub(crate) fn internal_upgrade(&mut self, code_hash: Base58CryptoHash) -> Promise {
+        require!(self.get_staged_code_hash() == Some(code_hash), "ERR_STAGED_CODE_CHANGED");
+        let code = env::storage_read(STAGED_CODE_KEY).expect("ERR_NO_STAGED_CODE");
+        env::storage_remove(STAGED_CODE_KEY);
+
+        Event::ContractUpgraded { code_hash, upgraded_by_account_id: &env::predecessor_account_id() }.emit();
+
+        Promise::new(env::current_account_id())
+            .deploy_contract(code)
+            .function_call("migrate".to_string(), vec![], 0, GAS_FOR_MIGRATE)
+            .function_call("get_state_version".to_string(), vec![], 0, GAS_FOR_STATE_CHECK)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::get_created_receipts;
+    use near_sdk::testing_env;
+    use crate::test_utils::*;
+    use super::*;
+
+    fn with_input(predecessor_id: &AccountId, input: &[u8]) {
+        let mut ctx = context(predecessor_id).build();
+        ctx.input = input.to_vec();
+        testing_env!(ctx);
+    }
+
+    fn stage(contract: &mut Contract, predecessor_id: &AccountId, chunk: &[u8]) {
+        with_input(predecessor_id, chunk);
+        contract.stage_code();
+    }
+
+    #[test]
+    fn staged_chunks_are_appended() {
+        let mut contract = new_contract();
+        stage(&mut contract, &guardian(), b"first chunk ");
+        stage(&mut contract, &guardian(), b"second chunk");
+        assert_eq!(contract.get_staged_code_hash(), Some(Base58CryptoHash::from(env::sha256_array(b"first chunk second chunk"))));
+
+        set_predecessor(&guardian());
+        contract.clear_staged_code();
+        assert!(contract.get_staged_code_hash().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACCESS")]
+    fn only_guardians_stage_code() {
+        let mut contract = new_contract();
+        stage(&mut contract, &stranger(), b"code");
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_STAGED_CODE_CHANGED")]
+    fn code_staged_after_the_proposal_is_not_deployed() {
+        let mut contract = new_contract();
+        contract.internal_add_guardian(&author(), &guardian());
+        stage(&mut contract, &guardian(), b"proposed code");
+        with_input(&guardian(), b"");
+        let proposal_id = contract.upgrade();
+
+        stage(&mut contract, &guardian(), b" with a tail");
+        set_predecessor(&author());
+        contract.approve_proposal(proposal_id);
+    }
+
+    #[test]
+    fn executed_upgrade_deploys_and_clears_staged_code() {
+        let mut contract = new_contract();
+        stage(&mut contract, &guardian(), b"new code");
+        // a single guardian executes the proposal right away
+        with_input(&guardian(), b"");
+        contract.upgrade();
+
+        assert!(contract.get_staged_code_hash().is_none());
+        let actions: Vec<VmAction> = get_created_receipts().into_iter().flat_map(|receipt| receipt.actions).collect();
+        assert_eq!(actions[0], VmAction::DeployContract { code: b"new code".to_vec() });
+        assert!(matches!(&actions[1], VmAction::FunctionCall { function_name, .. } if function_name == "migrate"));
+    }
+}
@@ -2,11 +2,11 @@ use near_sdk::is_promise_success;
 use crate::*;
 
 pub const GAS_FOR_WEB4_GET_CHECK_PROMISE_RESULT: Gas = Gas(Gas::ONE_TERA.0 * 40);
-pub const GAS_FOR_ON_WEB4_GET_CHECK_PROMISE_RESULT: Gas = Gas(Gas::ONE_TERA.0 * 30);
+// covers the retry below when the first web4_get fails
+pub const GAS_FOR_ON_WEB4_GET_CHECK_PROMISE_RESULT: Gas = Gas(Gas::ONE_TERA.0 * 80);
 pub const GAS_FOR_WEB4_GET_IGNORE_PROMISE_RESULT: Gas = Gas(Gas::ONE_TERA.0 * 10);
-pub const GAS_FOR_ON_WEB4_GET_IGNORE_PROMISE_RESULT: Gas = Gas(Gas::ONE_TERA.0 * 5);
-
-pub const LISTING_FEE: Balance = 100_000_000_000_000_000_000_000; // 0.1 NEAR
+// a listing with MAX_APP_SEARCH_TOKENS tokens
+pub const GAS_FOR_ON_WEB4_GET_IGNORE_PROMISE_RESULT: Gas = Gas(Gas::ONE_TERA.0 * 60);
 
 pub type WrappedBalance = U128;
 
@@ -14,7 +14,7 @@ pub type WrappedBalance = U128;
 impl Contract {
     #[payable]
     pub fn add_app(&mut self, app: AppJSON) {
-        require!(self.app_id_by_slug.get(&app.slug).is_none(), "ERR_SLUG_ALREADY_EXISTS");
+        self.assert_slug_available(&filter_slug(app.slug.clone()).to_lowercase(), &env::predecessor_account_id(), None);
         require!(self.app_id_by_dapp_account_id.get(&app.dapp_account_id).is_none(), "ERR_ACCOUNT_ID_ALREADY_EXISTS");
 
         require!(app.title.len() <= 50, "ERR_TITLE_IS_TOO_LONG");
@@ -23,7 +23,8 @@ impl Contract {
         require!(app.description.clone().unwrap_or_default().len() <= 5000, "ERR_DESCRIPTION_IS_TOO_LONG");
 
         if !self.guardians.contains(&env::predecessor_account_id()) {
-            require!(env::attached_deposit() >= LISTING_FEE, "ERR_LISTING_FEE_REQUIRED");
+            require!(env::attached_deposit() >= self.listing_fee, "ERR_LISTING_FEE_REQUIRED");
+            require!(env::attached_deposit() >= self.listing_fee + estimate_app_storage_cost(&app), "ERR_NOT_ENOUGH_STORAGE_DEPOSIT");
         }
 
         self.assert_web4(app.dapp_account_id.clone(), app, WrappedBalance::from(env::attached_deposit()), env::predecessor_account_id(), false);
@@ -33,41 +34,45 @@ impl Contract {
     pub fn after_web4_get(
         &mut self,
         #[callback_result] response: Result<Web4Response, PromiseError>,
-        mut app: AppJSON,
+        app: AppJSON,
         deposit: WrappedBalance,
         added_by_account_id: AccountId,
         ignore_promise_success: bool
     ) {
         if ignore_promise_success || is_promise_success() {
             match response {
-                Ok(_) => {
-                    let mut categories = UnorderedSet::new(StorageKey::AppCategories { app_id: self.next_app_id });
-                    for category_string in app.categories.clone() {
-                        let category_id: CategoryId = category_string.parse().expect("ERR_WRONG_CATEGORY");
-                        if self.categories.get(&category_id).is_some() {
-                            let mut apps_ids_by_category_id = self.apps_ids_by_category_id.get(&category_id).expect("ERR_NO_DATA");
-                            apps_ids_by_category_id.insert(&self.next_app_id);
-                            self.apps_ids_by_category_id.insert(&category_id, &apps_ids_by_category_id);
-                            categories.insert(&category_id);
-                        }
-                    }
-                    let mut contracts = UnorderedSet::new(StorageKey::AppContracts { app_id: self.next_app_id });
-                    let contracts_vec = if let Some(contracts_vec) = app.contracts.clone() { contracts_vec } else { vec![] };
-                    for contract in contracts_vec {
-                        contracts.insert(&contract);
+                Ok(response) => {
+                    let app_id = self.next_app_id;
+                    self.next_app_id += 1;
+
+                    // discovered metadata is charged together with the listing
+                    let storage_usage_before = env::storage_usage();
+                    // the retry callback has no gas to spare for parsing the page
+                    if !ignore_promise_success {
+                        self.internal_set_web4_metadata(app_id, &response, &app.dapp_account_id);
                     }
-                    app.active = Some(true);
-                    self.internal_insert_app(self.next_app_id, app, Some(added_by_account_id), categories, contracts);
 
-                    self.next_app_id += 1;
+                    let fee = std::cmp::min(deposit.0, self.listing_fee);
+                    if self.moderation_enabled && !self.guardians.contains(&added_by_account_id) {
+                        self.internal_add_pending_app(app_id, app, added_by_account_id, deposit, WrappedBalance::from(fee), false);
+                    } else {
+                        self.treasury += fee;
+                        self.internal_add_app(app_id, app, added_by_account_id.clone());
+                        self.internal_settle_app_storage(app_id, storage_usage_before, deposit.0 - fee, &added_by_account_id, &added_by_account_id, true);
+                    }
                 }
                 Err(_) => {
                     if deposit.0 > 0 {
-                        Promise::new(added_by_account_id).transfer(deposit.0);
+                        Promise::new(added_by_account_id.clone()).transfer(deposit.0);
                         log!("Deposit reverted");
                     }
 
                     log!("ERR_NOT_WEB4_APP");
+                    Event::ListingRejected {
+                        dapp_account_id: &app.dapp_account_id,
+                        added_by_account_id: &added_by_account_id,
+                        reason: "ERR_NOT_WEB4_APP",
+                    }.emit();
                 }
             }
         } else {
@@ -83,42 +88,9 @@ impl Contract {
     }
 
     pub fn assert_web4(&self, contract_id: AccountId, app: AppJSON, deposit: WrappedBalance, added_by_account_id: AccountId, send_request_with_data: bool) {
-        let (request, ignore_promise_success) = if send_request_with_data {
-            (
-                Web4Request {
-                    account_id: Some(contract_id.clone()),
-                    path: "".to_string(),
-                    params: Some(HashMap::new()),
-                    query: Some(HashMap::new()),
-                    preloads: Some(HashMap::new()),
-                },
-                true
-            )
-        } else {
-            (
-                Web4Request {
-                    account_id: Some(contract_id.clone()),
-                    path: "".to_string(),
-                    params: None,
-                    query: None,
-                    preloads: None,
-                },
-                false
-            )
-        };
+        let (web4_get, callback_gas, ignore_promise_success) = web4_get_probe(contract_id, send_request_with_data);
 
-        let (get_gas, callback_gas) =
-            if ignore_promise_success {
-                (GAS_FOR_WEB4_GET_IGNORE_PROMISE_RESULT, GAS_FOR_ON_WEB4_GET_IGNORE_PROMISE_RESULT)
-            } else {
-                (GAS_FOR_WEB4_GET_CHECK_PROMISE_RESULT, GAS_FOR_ON_WEB4_GET_CHECK_PROMISE_RESULT)
-            };
-
-        ext_web4::ext(contract_id)
-            .with_static_gas(get_gas)
-            .web4_get(
-                request
-            )
+        web4_get
             .then(
                 ext_self::ext(env::current_account_id())
                     .with_static_gas(callback_gas)
@@ -135,12 +107,71 @@ impl Contract {
         unordered_map_pagination(&self.apps, from_index, limit)
     }
 
+    pub(crate) fn internal_get_app_ids_by_author(&self, account_id: &AccountId, from_index: Option<u64>, limit: Option<u64>) -> Vec<AppId> {
+        self.app_ids_by_author.get(account_id)
+            .map(|app_ids| unordered_set_pagination(&app_ids, from_index.unwrap_or(0), limit.unwrap_or(u64::MAX)))
+            .unwrap_or_default()
+    }
+
     pub fn internal_get_app_by_slug(&self, slug: &String) -> (AppId, VApp) {
         let app_id = self.app_id_by_slug.get(slug).expect("ERR_NO_SLUG");
         (app_id, self.apps.get(&app_id).expect("ERR_NO_APP"))
     }
 }
 
+// web4_get call used by the listing and liveness checks, returns the call, gas for its callback and
+// whether the callback should ignore the promise status
+pub(crate) fn web4_get_probe(contract_id: AccountId, send_request_with_data: bool) -> (Promise, Gas, bool) {
+    let (request, ignore_promise_success) = if send_request_with_data {
+        (
+            Web4Request {
+                account_id: Some(contract_id.clone()),
+                path: "".to_string(),
+                params: Some(HashMap::new()),
+                query: Some(HashMap::new()),
+                preloads: Some(HashMap::new()),
+            },
+            true
+        )
+    } else {
+        (
+            Web4Request {
+                account_id: Some(contract_id.clone()),
+                path: "".to_string(),
+                params: None,
+                query: None,
+                preloads: None,
+            },
+            false
+        )
+    };
+
+    let (get_gas, callback_gas) =
+        if ignore_promise_success {
+            (GAS_FOR_WEB4_GET_IGNORE_PROMISE_RESULT, GAS_FOR_ON_WEB4_GET_IGNORE_PROMISE_RESULT)
+        } else {
+            (GAS_FOR_WEB4_GET_CHECK_PROMISE_RESULT, GAS_FOR_ON_WEB4_GET_CHECK_PROMISE_RESULT)
+        };
+
+    let web4_get = ext_web4::ext(contract_id)
+        .with_static_gas(get_gas)
+        .web4_get(
+            request
+        );
+
+    (web4_get, callback_gas, ignore_promise_success)
+}
+
+pub(crate) fn unordered_set_pagination<T>(s: &UnorderedSet<T>, from_index: u64, limit: u64) -> Vec<T>
+    where
+        T: BorshSerialize + BorshDeserialize,
+{
+    let values = s.as_vector();
+    (from_index..std::cmp::min(values.len(), from_index.saturating_add(limit)))
+        .map(|index| values.get(index).unwrap())
+        .collect()
+}
+
 pub(crate) fn unordered_map_pagination<K, VV, V>(
     m: &UnorderedMap<K, VV>,
     from_index: Option<u64>,
@@ -210,3 +241,54 @@ pub (crate) fn filter_html(s: Option<String>) -> Option<String> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::testing_env;
+    use crate::test_utils::*;
+    use super::*;
+
+    #[test]
+    fn longest_listing_fits_callback_gas() {
+        let mut contract = new_contract();
+        let mut app = app_json("longest", vec![]);
+        app.oneliner = Some((0..20).map(|index| format!("oneliner{}", index)).collect::<Vec<String>>().join(" "));
+        app.description = Some((0..450).map(|index| format!("word{}", index)).collect::<Vec<String>>().join(" "));
+
+        // the retry path gets the smallest allowance, running out of gas panics
+        testing_env!(context(&contract_account_id()).prepaid_gas(GAS_FOR_ON_WEB4_GET_IGNORE_PROMISE_RESULT).build());
+        contract.after_web4_get(Ok(dapp_response()), app, WrappedBalance::from(0), author(), true);
+
+        let app: App = contract.apps.get(&0).expect("ERR_NO_APP").into();
+        assert_eq!(app.search_tokens().len(), MAX_APP_SEARCH_TOKENS);
+        assert_eq!(contract.search_apps("longest".to_string(), None, None).len(), 1);
+    }
+
+    #[test]
+    fn longest_listing_fits_edit_form_gas() {
+        let form = include_str!("../res/edit.html");
+        let form_gas: u64 = form.split("name=\"web4_gas\" value=\"").nth(1).and_then(|rest| rest.split('"').next())
+            .expect("ERR_NO_WEB4_GAS").parse().expect("ERR_WEB4_GAS");
+
+        let mut contract = new_contract();
+        let mut app = app_json("longest", vec![]);
+        app.oneliner = Some((0..20).map(|index| format!("oneliner{}", index)).collect::<Vec<String>>().join(" "));
+        app.description = Some((0..450).map(|index| format!("word{}", index)).collect::<Vec<String>>().join(" "));
+        testing_env!(context(&contract_account_id()).build());
+        contract.after_web4_get(Ok(dapp_response()), app.clone(), WrappedBalance::from(0), author(), true);
+
+        // replacing every search token is the worst case for an edit
+        app.oneliner = Some((0..20).map(|index| format!("edited{}", index)).collect::<Vec<String>>().join(" "));
+        app.description = Some((0..450).map(|index| format!("changed{}", index)).collect::<Vec<String>>().join(" "));
+        testing_env!(context(&author())
+            .prepaid_gas(Gas(form_gas))
+            .attached_deposit(contract.update_fee + 1_000_000_000_000_000_000_000_000)
+            .build());
+        contract.update_app_by_account_id(app);
+
+        let app: App = contract.apps.get(&0).expect("ERR_NO_APP").into();
+        assert_eq!(app.search_tokens().len(), MAX_APP_SEARCH_TOKENS);
+        assert_eq!(contract.search_apps("edited0".to_string(), None, None).len(), 1);
+        assert!(contract.search_apps("oneliner0".to_string(), None, None).is_empty());
+    }
+}
@@ -0,0 +1,300 @@
+use crate::*;
+
+// the dapp proves the listing owner by serving the challenge from this web4 path
+pub const OWNER_VERIFICATION_PATH: &str = "/.well-known/awesomeweb4.txt";
+const GAS_FOR_OWNER_VERIFICATION_WEB4_GET: Gas = Gas(Gas::ONE_TERA.0 * 40);
+const GAS_FOR_AFTER_OWNER_VERIFICATION_WEB4_GET: Gas = Gas(Gas::ONE_TERA.0 * 15);
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AppContractJSON {
+    pub account_id: AccountId,
+    pub verified: bool,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_app_by_contract(&self, account_id: AccountId) -> (AppId, AppJSON) {
+        let app_id = self.app_id_by_verified_contract.get(&account_id).expect("ERR_CONTRACT_IS_NOT_VERIFIED");
+        (app_id, self.apps.get(&app_id).expect("ERR_NO_APP").into())
+    }
+
+    pub fn is_app_owner_verified(&self, app_id: AppId) -> bool {
+        let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+        self.internal_is_app_owner_verified(app_id, &app)
+    }
+
+    pub fn get_owner_verification_challenge(&self, app_id: AppId) -> String {
+        let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+        owner_verification_challenge(app_id, &app.added_by_account_id)
+    }
+
+    // the dapp account confirms the owner directly, the owner asks the contract to read the challenge from the dapp
+    pub fn verify_app_owner(&mut self, app_id: AppId) {
+        let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+        if env::predecessor_account_id() == app.dapp_account_id {
+            self.internal_set_app_owner_verified(app_id, &app.added_by_account_id);
+            return;
+        }
+        require!(env::predecessor_account_id() == app.added_by_account_id, "ERR_NO_ACCESS");
+
+        ext_web4::ext(app.dapp_account_id)
+            .with_static_gas(GAS_FOR_OWNER_VERIFICATION_WEB4_GET)
+            .web4_get(Web4Request {
+                account_id: Some(env::predecessor_account_id()),
+                path: OWNER_VERIFICATION_PATH.to_string(),
+                params: None,
+                query: None,
+                preloads: None,
+            })
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_AFTER_OWNER_VERIFICATION_WEB4_GET)
+                    .after_owner_verification_web4_get(app_id, app.added_by_account_id)
+            );
+    }
+
+    #[private]
+    pub fn after_owner_verification_web4_get(
+        &mut self,
+        #[callback_result] response: Result<Web4Response, PromiseError>,
+        app_id: AppId,
+        owner_id: AccountId
+    ) -> bool {
+        // ownership could change while the dapp was queried
+        let app: Option<App> = self.apps.get(&app_id).map(|v_app| v_app.into());
+        if app.map(|app| app.added_by_account_id != owner_id).unwrap_or(true) {
+            log!("ERR_APP_OWNER_CHANGED");
+            return false;
+        }
+
+        let challenge = owner_verification_challenge(app_id, &owner_id);
+        match response {
+            Ok(Web4Response::Body { body, .. }) if String::from_utf8_lossy(&body.0).contains(&challenge) => {
+                self.internal_set_app_owner_verified(app_id, &owner_id);
+                true
+            }
+            _ => {
+                log!("ERR_OWNER_VERIFICATION_FAILED");
+                false
+            }
+        }
+    }
+
+    pub fn get_app_contracts(&self, app_id: AppId) -> Vec<AppContractJSON> {
+        let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+        app.contracts
+            .iter()
+            .map(|account_id| AppContractJSON {
+                verified: self.is_app_contract_verified(app_id, &account_id),
+                account_id,
+            })
+            .collect()
+    }
+
+    // declared contract confirms the listing by calling this method from its own account,
+    // a contract may back-link only one app at a time, verifying another app revokes the previous link
+    pub fn verify_app_contract(&mut self, app_id: AppId) {
+        let contract_id = env::predecessor_account_id();
+        let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+        require!(app.contracts.contains(&contract_id), "ERR_CONTRACT_IS_NOT_DECLARED");
+
+        if let Some(previous_app_id) = self.app_id_by_verified_contract.insert(&contract_id, &app_id) {
+            if previous_app_id != app_id {
+                Event::AppContractVerificationRevoked { app_id: previous_app_id, contract_id: &contract_id }.emit();
+            }
+        }
+
+        Event::AppContractVerified { app_id, contract_id: &contract_id }.emit();
+    }
+
+    pub fn revoke_app_contract_verification(&mut self) {
+        let contract_id = env::predecessor_account_id();
+        let app_id = self.app_id_by_verified_contract.remove(&contract_id).expect("ERR_CONTRACT_IS_NOT_VERIFIED");
+
+        Event::AppContractVerificationRevoked { app_id, contract_id: &contract_id }.emit();
+    }
+}
+
+impl Contract {
+    pub(crate) fn is_app_contract_verified(&self, app_id: AppId, contract_id: &AccountId) -> bool {
+        self.app_id_by_verified_contract.get(contract_id) == Some(app_id)
+    }
+
+    // verification is bound to the owner account, so an ownership transfer resets it
+    pub(crate) fn internal_is_app_owner_verified(&self, app_id: AppId, app: &App) -> bool {
+        self.verified_app_owners.get(&app_id).as_ref() == Some(&app.added_by_account_id)
+    }
+
+    pub(crate) fn internal_set_app_owner_verified(&mut self, app_id: AppId, owner_id: &AccountId) {
+        self.verified_app_owners.insert(&app_id, owner_id);
+
+        Event::AppOwnerVerified { app_id, owner_id }.emit();
+    }
+
+    // contracts no longer declared by the app lose their verification
+    pub(crate) fn internal_remove_app_contract_verifications(&mut self, app_id: AppId, app: &App) {
+        for contract_id in app.contracts.iter() {
+            if self.is_app_contract_verified(app_id, &contract_id) {
+                self.app_id_by_verified_contract.remove(&contract_id);
+            }
+        }
+    }
+}
+
+pub(crate) fn owner_verification_challenge(app_id: AppId, owner_id: &AccountId) -> String {
+    format!("awesomeweb4-verification={}:{}:{}", env::current_account_id(), app_id, owner_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::{get_created_receipts, get_logs};
+    use crate::test_utils::*;
+    use super::*;
+
+    fn token() -> AccountId {
+        "token.near".parse().unwrap()
+    }
+
+    fn list_app_with_token(contract: &mut Contract, slug: &str) -> AppId {
+        let mut app = app_json(slug, vec![]);
+        app.contracts = Some(vec![token()]);
+        list_app(contract, app, &author())
+    }
+
+    #[test]
+    fn declared_contract_links_one_app() {
+        let mut contract = new_contract();
+        let first_app_id = list_app_with_token(&mut contract, "first");
+        let second_app_id = list_app_with_token(&mut contract, "second");
+
+        set_predecessor(&token());
+        contract.verify_app_contract(first_app_id);
+        assert_eq!(contract.get_app_by_contract(token()).1.slug, "first");
+        assert!(contract.get_app_contracts(first_app_id)[0].verified);
+
+        set_predecessor(&token());
+        contract.verify_app_contract(second_app_id);
+        assert!(get_logs().iter().any(|log| log.contains("app_contract_verification_revoked")
+            && log.contains(&format!(r#""app_id":{}"#, first_app_id))));
+        assert_eq!(contract.get_app_by_contract(token()).0, second_app_id);
+        assert!(!contract.get_app_contracts(first_app_id)[0].verified);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_CONTRACT_IS_NOT_DECLARED")]
+    fn undeclared_contract_is_rejected() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+        set_predecessor(&token());
+        contract.verify_app_contract(app_id);
+    }
+
+    #[test]
+    fn removed_contract_loses_verification() {
+        let mut contract = new_contract();
+        let app_id = list_app_with_token(&mut contract, "dapp");
+        set_predecessor(&token());
+        contract.verify_app_contract(app_id);
+
+        set_predecessor(&guardian());
+        contract.update_app(app_id, app_json("dapp", vec![]));
+        assert!(contract.app_id_by_verified_contract.get(&token()).is_none());
+    }
+
+    #[test]
+    fn deleted_app_loses_verification() {
+        let mut contract = new_contract();
+        let app_id = list_app_with_token(&mut contract, "dapp");
+        set_predecessor(&token());
+        contract.verify_app_contract(app_id);
+
+        set_predecessor(&author());
+        contract.delete_app(app_id);
+        assert!(contract.app_id_by_verified_contract.get(&token()).is_none());
+    }
+
+    fn owner_verification_result(contract: &mut Contract, app_id: AppId, owner_id: AccountId, body: String) -> bool {
+        set_predecessor(&contract_account_id());
+        contract.after_owner_verification_web4_get(Ok(Web4Response::plain_response(body)), app_id, owner_id)
+    }
+
+    #[test]
+    fn dapp_account_verifies_owner_directly() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+        assert!(!contract.is_app_owner_verified(app_id));
+
+        set_predecessor(&"dapp.near".parse().unwrap());
+        contract.verify_app_owner(app_id);
+        assert!(contract.is_app_owner_verified(app_id));
+        assert!(get_created_receipts().is_empty());
+    }
+
+    #[test]
+    fn owner_is_verified_by_the_served_challenge() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+
+        set_predecessor(&author());
+        contract.verify_app_owner(app_id);
+        let receipt = get_created_receipts().into_iter().next().expect("ERR_NO_RECEIPT");
+        assert_eq!(receipt.receiver_id.as_str(), "dapp.near");
+        assert!(matches!(&receipt.actions[0], VmAction::FunctionCall { function_name, .. } if function_name == "web4_get"));
+
+        let body = format!("site verification\n{}\n", contract.get_owner_verification_challenge(app_id));
+        assert!(owner_verification_result(&mut contract, app_id, author(), body));
+        assert!(contract.is_app_owner_verified(app_id));
+    }
+
+    #[test]
+    fn page_without_the_challenge_is_rejected() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+        // a challenge for another owner does not count
+        let body = owner_verification_challenge(app_id, &stranger());
+
+        assert!(!owner_verification_result(&mut contract, app_id, author(), body));
+        assert!(get_logs().contains(&"ERR_OWNER_VERIFICATION_FAILED".to_string()));
+        assert!(!contract.is_app_owner_verified(app_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACCESS")]
+    fn strangers_cannot_request_owner_verification() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+        set_predecessor(&stranger());
+        contract.verify_app_owner(app_id);
+    }
+
+    #[test]
+    fn ownership_transfer_during_verification_is_rejected() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+        let body = contract.get_owner_verification_challenge(app_id);
+        set_predecessor(&author());
+        contract.transfer_app_ownership(app_id, stranger());
+        set_predecessor(&stranger());
+        contract.accept_app_ownership(app_id);
+
+        assert!(!owner_verification_result(&mut contract, app_id, author(), body));
+        assert!(get_logs().contains(&"ERR_APP_OWNER_CHANGED".to_string()));
+        assert!(!contract.is_app_owner_verified(app_id));
+    }
+
+    #[test]
+    fn ownership_transfer_resets_verification() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+        set_predecessor(&"dapp.near".parse().unwrap());
+        contract.verify_app_owner(app_id);
+
+        set_predecessor(&author());
+        contract.transfer_app_ownership(app_id, stranger());
+        set_predecessor(&stranger());
+        contract.accept_app_ownership(app_id);
+        assert!(!contract.is_app_owner_verified(app_id));
+    }
+}
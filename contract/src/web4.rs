@@ -1,5 +1,9 @@
 use crate::*;
 
+const SEARCH_RESULTS_LIMIT: u64 = 60;
+const DEFAULT_APPS_PER_PAGE: u64 = 30;
+const MAX_APPS_PER_PAGE: u64 = 100;
+
 #[ext_contract(ext_web4)]
 trait ExtWeb4Contract {
     fn web4_get(&self, request: Web4Request) -> Web4Response;
@@ -8,6 +12,9 @@ trait ExtWeb4Contract {
 #[ext_contract(ext_self)]
 trait ExtSelf {
     fn after_web4_get(&self, app: AppJSON, deposit: WrappedBalance, added_by_account_id: AccountId, ignore_promise_success: bool);
+    fn after_owner_verification_web4_get(&mut self, app_id: AppId, owner_id: AccountId) -> bool;
+    fn after_recheck_web4_get(&mut self, app_id: AppId, ignore_promise_success: bool);
+    fn after_withdraw_fees(&mut self, amount: WrappedBalance) -> bool;
 }
 
 #[allow(dead_code)]
@@ -122,13 +129,12 @@ impl Contract {
             return Web4Response::plain_response(include_str!("../res/manifest.json").to_string());
         }
 
-        if path == "/submit"  && !request.query.unwrap_or_default().contains_key("transactionHashes") {
+        if path == "/submit"  && !request.query.clone().unwrap_or_default().contains_key("transactionHashes") {
             let (show_login, form_visibility, user_account_id, categories_html) =
                 if let Some(user_account_id) = request.account_id {
                     let mut categories_html = "".to_string();
 
-                    for category_id in 0..self.next_category_id {
-                        let category: Category = self.categories.get(&category_id).expect("ERR_NO_CATEGORY").into();
+                    for (category_id, category) in self.internal_get_sorted_categories(false) {
                         categories_html = format!(r#"{}<div><label class="form-checkbox"><input type="checkbox" value="{}" name="app.categories[]" /><i class="form-icon"></i> {}</label></div>"#, categories_html, category_id, category.title);
                     }
 
@@ -150,28 +156,154 @@ impl Contract {
 
             return Web4Response::html_response(
                 include_str!("../res/submit.html")
-                    .replace("%CONTRACT_NAME%", &env::current_account_id().to_string())
+                    .replace("%CONTRACT_NAME%", env::current_account_id().as_ref())
                     .replace("%SHOW_LOGIN%",  &show_login)
                     .replace("%SHOW_FORM%", &form_visibility)
                     .replace("%USER_ACCOUNT_ID%", &user_account_id)
+                    .replace("%LISTING_FEE_NEAR%", &format_near_amount(self.listing_fee))
+                    .replace("%LISTING_DEPOSIT_NEAR%", &format_near_amount(self.listing_fee + SUBMIT_FORM_STORAGE_DEPOSIT))
+                    .replace("%LISTING_DEPOSIT%", &(self.listing_fee + SUBMIT_FORM_STORAGE_DEPOSIT).to_string())
                     .replace("%CATEGORIES_CHECKBOXES%", &categories_html)
-                    .replace("%FOOTER%", &include_str!("../res/footer.inc").to_string())
-                    .replace("%ICONS%", &include_str!("../res/icons.inc").to_string())
+                    .replace("%FOOTER%", include_str!("../res/footer.inc"))
+                    .replace("%ICONS%", include_str!("../res/icons.inc"))
+            );
+        }
+
+        if let Some(slug) = app_edit_slug(&path) {
+            let (app_id, v_app) = self.internal_get_app_by_slug(&slug.to_string());
+            let app: App = v_app.into();
+
+            let user_account_id = request.account_id;
+            let edit_by_guardian = user_account_id.as_ref().map(|account_id| self.has_role(account_id, Role::Moderator)).unwrap_or(false);
+            let edit_by_author = user_account_id.as_ref().map(|account_id|
+                &app.added_by_account_id == account_id || self.is_app_maintainer(app_id, account_id)).unwrap_or(false);
+
+            let (show_login, form_visibility, deposit_note, deposit) = if edit_by_guardian {
+                (
+                    "".to_string(),
+                    "block".to_string(),
+                    "Guardians edit apps without a fee.".to_string(),
+                    0
+                )
+            } else if edit_by_author {
+                let deposit = self.update_fee + SUBMIT_FORM_STORAGE_DEPOSIT;
+                (
+                    "".to_string(),
+                    "block".to_string(),
+                    format!("Save changes and deposit {} NEAR: {} NEAR update fee plus storage, unused storage deposit is refunded.",
+                            format_near_amount(deposit), format_near_amount(self.update_fee)),
+                    deposit
+                )
+            } else if user_account_id.is_some() {
+                (
+                    r#"<h2 class="hero-subtitle">Only the app author, maintainers and guardians can edit this app.</h2>"#.to_string(),
+                    "none".to_string(),
+                    "".to_string(),
+                    0
+                )
+            } else {
+                (
+                    format!(r#"<h2 class="hero-subtitle">Sign in with NEAR account to edit the app.</h2><div><a href="/web4/login?web4_contract_id={}" class="btn btn-primary">Sing in</a></div>"#, env::current_account_id()),
+                    "none".to_string(),
+                    "".to_string(),
+                    0
+                )
+            };
+
+            // fields missing in the form are sent as they are to keep them unchanged
+            let mut hidden_fields = "".to_string();
+            let mut categories_html = "".to_string();
+            for (category_id, category) in self.internal_get_sorted_categories(true) {
+                let checked = app.categories.contains(&category_id);
+                if category.hidden {
+                    if checked {
+                        hidden_fields = format!(r#"{}<input type="hidden" name="app.categories[]" value="{}">"#, hidden_fields, category_id);
+                    }
+                    continue;
+                }
+                let checked = if checked { " checked" } else { "" };
+                categories_html = format!(r#"{}<div><label class="form-checkbox"><input type="checkbox" value="{}" name="app.categories[]"{} /><i class="form-icon"></i> {}</label></div>"#, categories_html, category_id, checked, category.title);
+            }
+            for contract_id in app.contracts.iter() {
+                hidden_fields = format!(r#"{}<input type="hidden" name="app.contracts[]" value="{}">"#, hidden_fields, contract_id);
+            }
+            if let Some(token_address) = app.token_address.as_ref() {
+                hidden_fields = format!(r#"{}<input type="hidden" name="app.token_address" value="{}">"#, hidden_fields, token_address);
+            }
+            if let Some(symbol) = app.symbol.as_ref() {
+                hidden_fields = format!(r#"{}<input type="hidden" name="app.symbol" value="{}">"#, hidden_fields, symbol);
+            }
+
+            return Web4Response::html_response(
+                include_str!("../res/edit.html")
+                    .replace("%CONTRACT_NAME%", env::current_account_id().as_ref())
+                    .replace("%SHOW_LOGIN%",  &show_login)
+                    .replace("%SHOW_FORM%", &form_visibility)
+                    .replace("%USER_ACCOUNT_ID%", &user_account_id.map(|account_id| account_id.to_string()).unwrap_or_default())
+                    .replace("%DEPOSIT_NOTE%", &deposit_note)
+                    .replace("%UPDATE_DEPOSIT%", &deposit.to_string())
+                    .replace("%HIDDEN_FIELDS%", &hidden_fields)
+                    .replace("%CATEGORIES_CHECKBOXES%", &categories_html)
+                    .replace("%APP_PAGE_TITLE%", &app.title)
+                    .replace("%APP_PAGE_SLUG%", &app.slug)
+                    .replace("%APP_TITLE%", &app.title)
+                    .replace("%APP_DAPP_ACCOUNT_ID%", app.dapp_account_id.as_ref())
+                    .replace("%APP_SLUG%", &app.slug)
+                    .replace("%APP_ONELINER%", &app.oneliner.unwrap_or_default())
+                    .replace("%APP_DESCRIPTION%", &app.description.unwrap_or_default())
+                    .replace("%APP_LOGO_URL%", &app.logo_url.unwrap_or_default())
+                    .replace("%APP_TWITTER%", &app.twitter.unwrap_or_default())
+                    .replace("%APP_FACEBOOK%", &app.facebook.unwrap_or_default())
+                    .replace("%APP_MEDIUM%", &app.medium.unwrap_or_default())
+                    .replace("%APP_TELEGRAM%", &app.telegram.unwrap_or_default())
+                    .replace("%APP_GITHUB%", &app.github.unwrap_or_default())
+                    .replace("%APP_DISCORD%", &app.discord.unwrap_or_default())
+                    .replace("%FOOTER%", include_str!("../res/footer.inc"))
+                    .replace("%ICONS%", include_str!("../res/icons.inc"))
             );
         }
 
         if path.starts_with("/app/") {
             let slug = &path[5..]; // 5 = "/app/".len()
             let (app_id, v_app) = self.internal_get_app_by_slug(&slug.to_string());
-            let app: App = v_app.into();
+            let mut app: App = v_app.into();
+            app.apply_web4_metadata(self.web4_metadata_by_app_id.get(&app_id));
 
             let mut tags_html: String = "".to_string();
             for category_id in app.categories.to_vec() {
-                let category_data: Category = self.categories.get(&category_id).expect("ERR_WRONG_CATEGORY").into();
-                tags_html = format!(r#"{}<a class="tag-item awesome-tag" href="/category/{}">{}</a>"#, tags_html, category_data.slug, category_data.title);
+                if let Some(category_data) = self.categories.get(&category_id) {
+                    let category_data: Category = category_data.into();
+                    tags_html = format!(r#"{}<a class="tag-item awesome-tag" href="/category/{}">{}</a>"#, tags_html, category_data.slug, category_data.title);
+                }
             }
             let category_html = format!(r#"<div class="hero-tags">{}</div>"#, &tags_html);
 
+            let maintainers = self.get_app_maintainers(app_id);
+            let maintainers_html = if maintainers.is_empty() {
+                "".to_string()
+            } else {
+                let maintainers: Vec<String> = maintainers.iter().map(|account_id| account_id.to_string()).collect();
+                format!(r#" <small class="label label-secondary">Maintainers: {}</small>"#, maintainers.join(", "))
+            };
+
+            let broken_badge = if app.is_broken() {
+                r#" <small class="label label-error">Broken: web4 page is not responding</small>"#
+            } else {
+                ""
+            };
+
+            let owner_badge = if self.internal_is_app_owner_verified(app_id, &app) {
+                r#" <small class="label label-success">Verified owner</small>"#
+            } else {
+                ""
+            };
+
+            let mut contracts_html = "".to_string();
+            for contract in self.get_app_contracts(app_id) {
+                let (label_class, status) = if contract.verified { ("label-success", "verified") } else { ("label-secondary", "unverified") };
+                contracts_html = format!(r#"{} <small class="label {}">Contract: {} ({})</small>"#, contracts_html, label_class, contract.account_id, status);
+            }
+
             let social_links = format!("{}{}{}{}{}{}",
                                        format_icon(app.twitter, "twitter", false),
                                        format_icon(app.facebook, "facebook", false),
@@ -187,88 +319,272 @@ impl Contract {
 
             return Web4Response::html_response(
                 include_str!("../res/app.html")
-                    .replace("%CONTRACT_NAME%", &env::current_account_id().to_string())
+                    .replace("%CONTRACT_NAME%", env::current_account_id().as_ref())
                     .replace("%APP_PAGE_TITLE%", &app.title)
                     .replace("%APP_PAGE_IMAGE%", &image_url)
-                    .replace("%APP_PAGE_DAPP_CONTRACT%", &app.dapp_account_id.to_string())
+                    .replace("%APP_PAGE_DAPP_CONTRACT%", app.dapp_account_id.as_ref())
                     .replace("%APP_PAGE_ONELINER%", &app.oneliner.unwrap_or_default())
                     .replace("%APP_PAGE_DESCRIPTION%", &app.description.unwrap_or_default().replace('\n', "</p><p>"))
                     .replace("%APP_PAGE_CATEGORIES%", &category_html)
                     .replace("%APP_PAGE_SOCIAL_LINKS%", &social_links)
                     .replace("%APP_PAGE_SLUG%", &app.slug)
                     .replace("%APP_PAGE_ADDED_BY%", &app.added_by_account_id.to_string())
+                    .replace("%APP_PAGE_MAINTAINERS%", &maintainers_html)
+                    .replace("%APP_PAGE_CONTRACTS%", &contracts_html)
+                    .replace("%APP_PAGE_OWNER_BADGE%", owner_badge)
+                    .replace("%APP_PAGE_BROKEN_BADGE%", broken_badge)
                     .replace("%APP_PAGE_ID%", &app_id.to_string())
-                    .replace("%FOOTER%", &include_str!("../res/footer.inc").to_string())
-                    .replace("%ICONS%", &include_str!("../res/icons.inc").to_string())
+                    .replace("%FOOTER%", include_str!("../res/footer.inc"))
+                    .replace("%ICONS%", include_str!("../res/icons.inc"))
 
             );
         }
 
+        if path == "/my" {
+            let (page, per_page, from_index) = get_page_params(&request.query.clone().unwrap_or_default());
+            let mut app_html: String = "".to_string();
+            let mut total_apps: u64 = 0;
+            if let Some(user_account_id) = request.account_id.clone() {
+                total_apps = self.app_ids_by_author.get(&user_account_id).map(|app_ids| app_ids.len()).unwrap_or(0);
+                for app_id in self.internal_get_app_ids_by_author(&user_account_id, Some(from_index), Some(per_page)) {
+                    let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+                    app_html = format!("{}{}", app_html, self.format_app(app_id, app, true));
+                }
+                if app_html.is_empty() {
+                    app_html = r#"<div class="column col-12"><h2 class="hero-subtitle">You have no apps yet.</h2><div><a href="/submit" class="btn btn-primary">Submit app</a></div></div>"#.to_string();
+                }
+            } else {
+                app_html = format!(r#"<div class="column col-12"><h2 class="hero-subtitle">Sign in with NEAR account to see your apps.</h2><div><a href="/web4/login?web4_contract_id={}" class="btn btn-primary">Sing in</a></div></div>"#, env::current_account_id());
+            }
+
+            return Web4Response::html_response(
+                include_str!("../res/catalog.html")
+                    .replace("%CONTRACT_NAME%", env::current_account_id().as_ref())
+                    .replace("%APPLICATIONS%", &app_html)
+                    .replace("%CATEGORIES%", &self.format_categories_menu(None))
+                    .replace("%CATALOG_SUBTITLE%", " - My apps")
+                    .replace("%CATEGORY_LINK%", "my")
+                    .replace("%SEARCH_QUERY%", "")
+                    .replace("%PAGINATION%", &format_pagination("/my", page, per_page, total_apps))
+                    .replace("%FOOTER%", include_str!("../res/footer.inc"))
+                    .replace("%ICONS%", include_str!("../res/icons.inc"))
+            );
+        }
+
+        if path == "/admin" {
+            let user_account_id = request.account_id;
+            let user_has_role = |role: Role| user_account_id.as_ref().map(|account_id| self.has_role(account_id, role)).unwrap_or(false);
+            let is_guardian = user_has_role(Role::Owner);
+            let is_moderator = user_has_role(Role::Moderator);
+            let is_curator = user_has_role(Role::Curator);
+
+            let (show_login, admin_visibility) = if is_guardian || is_moderator || is_curator {
+                ("".to_string(), "block".to_string())
+            } else if user_account_id.is_some() {
+                (r#"<h2 class="hero-subtitle">Only guardians, moderators and curators have access to this page.</h2>"#.to_string(), "none".to_string())
+            } else {
+                (format!(r#"<h2 class="hero-subtitle">Sign in with guardian account.</h2><div><a href="/web4/login?web4_contract_id={}" class="btn btn-primary">Sing in</a></div>"#, env::current_account_id()),
+                 "none".to_string())
+            };
+
+            let mut disabled_apps_html = "".to_string();
+            let mut categories_html = "".to_string();
+            let mut guardians_html = "".to_string();
+            let mut proposals_html = "".to_string();
+            if is_guardian {
+                for (proposal_id, proposal) in self.get_proposals(None, None) {
+                    if proposal.status == ProposalStatus::InProgress {
+                        proposals_html = format!(r#"{}<div>#{} <code>{}</code> by {}, approved by {}</div>"#,
+                                                 proposals_html, proposal_id,
+                                                 near_sdk::serde_json::to_string(&proposal.action).unwrap_or_default(),
+                                                 proposal.proposer_id, proposal.approvals.len());
+                    }
+                }
+                if proposals_html.is_empty() {
+                    proposals_html = "<p>No open proposals.</p>".to_string();
+                }
+
+                for guardian_id in self.guardians.iter() {
+                    guardians_html = format!(r#"{}<form method="POST" action="/web4/contract/{}/remove_guardian"><input type="hidden" name="web4_callback_url" value="https://{}.page/admin"><input type="hidden" name="account_id" value="{}"><div>{} <input class="btn btn-sm" type="submit" value="Remove"></div></form>"#,
+                                             guardians_html, env::current_account_id(), env::current_account_id(), guardian_id, guardian_id);
+                }
+            }
+
+            if is_moderator {
+                let (page, per_page, from_index) = get_page_params(&request.query.unwrap_or_default());
+                for app_id in unordered_set_pagination(&self.disabled_app_ids, from_index, per_page) {
+                    let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+                    disabled_apps_html = format!(r#"{}<form method="POST" action="/web4/contract/{}/enable_app_by_account_id"><input type="hidden" name="web4_callback_url" value="https://{}.page/admin"><input type="hidden" name="account_id" value="{}"><div><a href="/app/{}">{}</a> ({}) <input class="btn btn-sm" type="submit" value="Enable"></div></form>"#,
+                                                 disabled_apps_html, env::current_account_id(), env::current_account_id(), app.dapp_account_id, app.slug, app.title, app.dapp_account_id);
+                }
+                if disabled_apps_html.is_empty() {
+                    disabled_apps_html = "<p>No disabled apps.</p>".to_string();
+                }
+                disabled_apps_html = format!("{}{}", disabled_apps_html, format_pagination("/admin", page, per_page, self.disabled_app_ids.len()));
+            }
+
+            if is_curator {
+                for (category_id, category) in self.internal_get_sorted_categories(true) {
+                    let apps_count = self.apps_ids_by_category_id.get(&category_id).map(|app_ids| app_ids.len()).unwrap_or(0);
+                    let hidden_badge = if category.hidden { r#" <span class="label">hidden</span>"# } else { "" };
+                    categories_html = format!(r#"{}<div>#{} <a href="/category/{}">{}</a> — {} apps{}</div>"#,
+                                              categories_html, category_id, category.slug, category.title, apps_count, hidden_badge);
+                }
+            }
+
+            let section_visibility = |visible: bool| if visible { "block" } else { "none" };
+
+            return Web4Response::html_response(
+                include_str!("../res/admin.html")
+                    .replace("%CONTRACT_NAME%", env::current_account_id().as_ref())
+                    .replace("%SHOW_LOGIN%", &show_login)
+                    .replace("%SHOW_ADMIN%", &admin_visibility)
+                    .replace("%SHOW_OWNER%", section_visibility(is_guardian))
+                    .replace("%SHOW_MODERATOR%", section_visibility(is_moderator))
+                    .replace("%SHOW_CURATOR%", section_visibility(is_curator))
+                    .replace("%USER_ACCOUNT_ID%", &user_account_id.map(|account_id| account_id.to_string()).unwrap_or_default())
+                    .replace("%DISABLED_APPS%", &disabled_apps_html)
+                    .replace("%CATEGORIES%", &categories_html)
+                    .replace("%GUARDIANS%", &guardians_html)
+                    .replace("%PROPOSALS%", &proposals_html)
+                    .replace("%APPROVAL_THRESHOLD%", &self.internal_approval_threshold().to_string())
+                    .replace("%TREASURY_NEAR%", &format_near_amount(self.treasury))
+                    .replace("%LISTING_FEE_NEAR%", &format_near_amount(self.listing_fee))
+                    .replace("%LISTING_FEE%", &self.listing_fee.to_string())
+                    .replace("%UPDATE_FEE_NEAR%", &format_near_amount(self.update_fee))
+                    .replace("%UPDATE_FEE%", &self.update_fee.to_string())
+                    .replace("%FOOTER%", include_str!("../res/footer.inc"))
+                    .replace("%ICONS%", include_str!("../res/icons.inc"))
+            );
+        }
+
+        if path == "/search" {
+            let query = filter_text(get_query_param(&request.query.unwrap_or_default(), "q")).unwrap_or_default();
+
+            let mut app_html: String = "".to_string();
+            for app_id in self.internal_search_apps(&query, None, Some(SEARCH_RESULTS_LIMIT)) {
+                let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+                app_html = format!("{}{}", app_html, self.format_app(app_id, app, false));
+            }
+            if app_html.is_empty() {
+                app_html = r#"<div class="column col-12"><h2 class="hero-subtitle">No apps found.</h2></div>"#.to_string();
+            }
+
+            return Web4Response::html_response(
+                include_str!("../res/catalog.html")
+                    .replace("%CONTRACT_NAME%", env::current_account_id().as_ref())
+                    .replace("%APPLICATIONS%", &app_html)
+                    .replace("%CATEGORIES%", &self.format_categories_menu(None))
+                    .replace("%CATALOG_SUBTITLE%", &format!(" - Search: {}", query))
+                    .replace("%CATEGORY_LINK%", "search")
+                    .replace("%SEARCH_QUERY%", &query)
+                    .replace("%PAGINATION%", "")
+                    .replace("%FOOTER%", include_str!("../res/footer.inc"))
+                    .replace("%ICONS%", include_str!("../res/icons.inc"))
+            );
+        }
+
         // MAIN PAGE
+        let (page, per_page, from_index) = get_page_params(&request.query.unwrap_or_default());
+
         let mut active_category_id: Option<CategoryId> = None;
         let mut app_html: String = "".to_string();
         let mut category_link: String = "".to_string();
         let mut category_subtitle: String = "".to_string();
+        let mut total_apps: u64 = 0;
         // APPS for a specific category
         if path.starts_with("/category/") {
             let slug = &path[10..]; // 10 = "/category/".len()
-            for category_id in 0..self.next_category_id {
+            if let Some(category_id) = self.category_id_by_slug.get(&slug.to_string()) {
                 let category: Category = self.categories.get(&category_id).expect("ERR_NO_CATEGORY").into();
-                if category.slug == slug {
+                // category sets hold active apps only, sub-categories are merged into the parent category page
+                let app_ids: Vec<AppId> = if self.internal_get_descendant_category_ids(category_id).len() == 1 {
                     let app_ids = self.apps_ids_by_category_id.get(&category_id).expect("ERR_NO_CATEGORY");
-                    for app_id in app_ids.as_vector().iter() {
-                        let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
-                        if app.active.unwrap_or(true) {
-                            app_html = format!("{}{}", app_html, self.format_app(app));
-                        }
-                    }
-                    active_category_id = Some(category_id);
-                    category_link = format!("category/{}", slug);
-                    category_subtitle = format!(" - {}", category.title);
-                    break;
+                    total_apps = app_ids.len();
+                    unordered_set_pagination(&app_ids, from_index, per_page)
+                } else {
+                    let app_ids = self.internal_get_category_tree_app_ids(category_id);
+                    total_apps = app_ids.len() as u64;
+                    app_ids.into_iter().skip(from_index as usize).take(per_page as usize).collect()
+                };
+                for app_id in app_ids {
+                    let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+                    app_html = format!("{}{}", app_html, self.format_app(app_id, app, false));
                 }
+                active_category_id = Some(category_id);
+                category_link = format!("category/{}", slug);
+                category_subtitle = format!(" - {}", category.title);
             }
         } else { // ALL APPS
-            for (app_id, app) in self.internal_get_apps(None, None) {
-                if app.active.unwrap_or(true) {
-                    app_html = format!("{}{}", app_html, self.format_app(app));
-                }
+            total_apps = self.active_app_ids.len();
+            for app_id in unordered_set_pagination(&self.active_app_ids, from_index, per_page) {
+                let app: App = self.apps.get(&app_id).expect("ERR_NO_APP").into();
+                app_html = format!("{}{}", app_html, self.format_app(app_id, app, false));
             }
         }
 
-
         Web4Response::html_response(
             include_str!("../res/catalog.html")
-                .replace("%CONTRACT_NAME%", &env::current_account_id().to_string())
+                .replace("%CONTRACT_NAME%", env::current_account_id().as_ref())
                 .replace("%APPLICATIONS%", &app_html)
                 .replace("%CATEGORIES%", &self.format_categories_menu(active_category_id))
                 .replace("%CATALOG_SUBTITLE%", &category_subtitle)
                 .replace("%CATEGORY_LINK%", &category_link)
-                .replace("%FOOTER%", &include_str!("../res/footer.inc").to_string())
-                .replace("%ICONS%", &include_str!("../res/icons.inc").to_string())
+                .replace("%SEARCH_QUERY%", "")
+                .replace("%PAGINATION%", &format_pagination(&format!("/{}", category_link), page, per_page, total_apps))
+                .replace("%FOOTER%", include_str!("../res/footer.inc"))
+                .replace("%ICONS%", include_str!("../res/icons.inc"))
         )
     }
 }
 
 impl Contract {
     fn format_categories_menu(&self, active_category_id: Option<CategoryId>) -> String {
-        let set_active_category = active_category_id.is_some();
-        let active_category_id: CategoryId = active_category_id.unwrap_or_default();
+        let mut categories_by_parent = self.internal_get_categories_by_parent(true);
+        let (categories_html, _) = self.format_categories_submenu(&mut categories_by_parent, None, active_category_id);
+        format!(r#"<div><a class="menu-parent" href="/">All<span class="menu-badge">{}</span></a></div>{}"#, self.active_apps, categories_html)
+    }
+
+    // hidden categories are skipped together with their sub-categories, but still count towards their parents
+    // returns the html with the sorted ids of the apps in the rendered level, so every category set is read once
+    fn format_categories_submenu(&self, categories_by_parent: &mut HashMap<Option<CategoryId>, Vec<(CategoryId, Category)>>,
+                                 parent_id: Option<CategoryId>, active_category_id: Option<CategoryId>) -> (String, Vec<AppId>) {
         let mut categories_html = "".to_string();
-        for (category_id, category) in self.get_categories(None, None) {
-            let active_class = if set_active_category && category_id == active_category_id { " active" } else { "" };
-            let apps_in_category = self.apps_ids_by_category_id.get(&category_id).expect("ERR_NO_CATEGORY").len();
-            categories_html = format!(r#"{}<div><a class="menu-parent{}" href="/category/{}">{}<span class="menu-badge">{}</span></a></div>"#, categories_html, active_class, category.slug, category.title, apps_in_category);
+        let mut level_app_ids = vec![];
+        for (category_id, category) in categories_by_parent.remove(&parent_id).unwrap_or_default() {
+            let (children_html, mut app_ids) = self.format_categories_submenu(categories_by_parent, Some(category_id), active_category_id);
+            if let Some(category_app_ids) = self.apps_ids_by_category_id.get(&category_id) {
+                app_ids.extend(category_app_ids.iter());
+            }
+            app_ids.sort_unstable();
+            app_ids.dedup();
+            let apps_in_category = app_ids.len();
+            level_app_ids.extend(app_ids);
+            if category.hidden {
+                continue;
+            }
+            let active_class = if Some(category_id) == active_category_id { " active" } else { "" };
+            let children_html = if children_html.is_empty() { children_html } else { format!(r#"<div class="menu-nav">{}</div>"#, children_html) };
+            let item_class = if parent_id.is_some() { r#" class="menu-item""# } else { "" };
+            categories_html = format!(r#"{}<div{}><a class="menu-parent{}" href="/category/{}">{}<span class="menu-badge">{}</span></a>{}</div>"#, categories_html, item_class, active_class, category.slug, category.title, apps_in_category, children_html);
         }
-        categories_html = format!(r#"<div><a class="menu-parent" href="/">All<span class="menu-badge">{}</span></a></div>{}"#, (self.next_app_id - self.disabled_apps), categories_html);
-        categories_html
+        level_app_ids.sort_unstable();
+        level_app_ids.dedup();
+        (categories_html, level_app_ids)
     }
 
-    fn format_app(&self, app: App) -> String {
+    fn format_app(&self, app_id: AppId, mut app: App, edit_link: bool) -> String {
+        app.apply_web4_metadata(self.web4_metadata_by_app_id.get(&app_id));
         let mut tags_html: String = "".to_string();
         for category_id in app.categories.to_vec() {
-            let category_data: Category = self.categories.get(&category_id).expect("ERR_WRONG_CATEGORY").into();
-            tags_html = format!("{}<span>{}</span>", tags_html, category_data.title);
+            if let Some(category_data) = self.categories.get(&category_id) {
+                let category_data: Category = category_data.into();
+                tags_html = format!("{}<span>{}</span>", tags_html, category_data.title);
+            }
+        }
+
+        if app.is_broken() {
+            tags_html = format!(r#"{}<span class="label label-error">Broken</span>"#, tags_html);
         }
 
         let mut image_url = app.logo_url.unwrap_or_default();
@@ -276,6 +592,12 @@ impl Contract {
             image_url = "/no-image.svg".to_string();
         }
 
+        let edit_html = if edit_link {
+            format!(r#"<a class="btn btn-sm btn-primary" href="{}">Edit</a>"#, app_edit_path(&app.slug))
+        } else {
+            "".to_string()
+        };
+
         format!(r##"
 <div class="column col-4 col-lg-6 col-sm-12">
     <div style="padding: 1rem" class="near-item mainnet">
@@ -300,6 +622,7 @@ impl Contract {
                 {}
                 {}
                 {}
+                {}
            </div>
            <div class="tile-series">
                <div class="label-series near">
@@ -320,11 +643,62 @@ impl Contract {
                 format_icon(app.medium, "medium", true),
                 format_icon(app.telegram, "telegram", true),
                 format_icon(app.github, "github", true),
-                format_icon(app.discord, "discord", true)
+                format_icon(app.discord, "discord", true),
+                edit_html
         )
     }
 }
 
+fn get_query_param(query: &HashMap<String, Vec<String>>, key: &str) -> Option<String> {
+    query.get(key).and_then(|values| values.first().cloned())
+}
+
+// returns page, per_page and from_index
+fn get_page_params(query: &HashMap<String, Vec<String>>) -> (u64, u64, u64) {
+    let page = get_query_param(query, "page")
+        .and_then(|page| page.parse::<u64>().ok())
+        .unwrap_or(1)
+        .max(1);
+    let per_page = get_query_param(query, "per_page")
+        .and_then(|per_page| per_page.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_APPS_PER_PAGE)
+        .clamp(1, MAX_APPS_PER_PAGE);
+    (page, per_page, (page - 1).saturating_mul(per_page))
+}
+
+// edit page of the app, served by web4_get
+fn app_edit_path(slug: &str) -> String {
+    format!("/app/{}/edit", slug)
+}
+
+// slug of an edit page path, "/app/edit" is the page of an app with the "edit" slug
+fn app_edit_slug(path: &str) -> Option<&str> {
+    path.strip_prefix("/app/")
+        .and_then(|path| path.strip_suffix("/edit"))
+        .filter(|slug| !slug.is_empty())
+}
+
+fn format_pagination(base_link: &str, page: u64, per_page: u64, total_items: u64) -> String {
+    let total_pages = total_items.div_ceil(per_page);
+    if total_pages <= 1 {
+        return "".to_string();
+    }
+
+    let format_link = |page: u64, title: &str, class: &str, enabled: bool| {
+        if enabled {
+            format!(r#"<li class="page-item {}"><a href="{}?page={}&per_page={}">{}</a></li>"#, class, base_link, page, per_page, title)
+        } else {
+            format!(r#"<li class="page-item {} disabled"><a href="" tabindex="-1">{}</a></li>"#, class, title)
+        }
+    };
+
+    format!(r#"<ul class="pagination">{}<li class="page-item"><span>Page {} of {}</span></li>{}</ul>"#,
+            format_link(page.saturating_sub(1), "Previous", "page-prev", page > 1),
+            page,
+            total_pages,
+            format_link(page.saturating_add(1), "Next", "page-next", page < total_pages))
+}
+
 fn format_icon(value: Option<String>, icon: &str, homepage: bool) -> String {
     if let Some(value) = value {
         if !value.is_empty() {
@@ -347,4 +721,195 @@ fn format_icon(value: Option<String>, icon: &str, homepage: bool) -> String {
     }
 
     "".to_string()
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use near_sdk::testing_env;
+    use crate::test_utils::*;
+    use super::*;
+
+    // gas a view call may burn on the RPC node
+    const VIEW_GAS: Gas = Gas(Gas::ONE_TERA.0 * 200);
+    const APPS: u64 = 3_000;
+
+    fn render(contract: &Contract, path: &str, page: u64) -> String {
+        render_as(contract, None, path, page)
+    }
+
+    fn render_as(contract: &Contract, account_id: Option<AccountId>, path: &str, page: u64) -> String {
+        testing_env!(context(&stranger()).is_view(true).prepaid_gas(VIEW_GAS).build());
+        let mut query = HashMap::new();
+        query.insert("page".to_string(), vec![page.to_string()]);
+        let request = Web4Request { account_id, path: path.to_string(), params: None, query: Some(query), preloads: None };
+        match contract.web4_get(request) {
+            Web4Response::Body { body, .. } => String::from_utf8(body.0).unwrap(),
+            _ => panic!("ERR_NOT_A_BODY"),
+        }
+    }
+
+    fn rendered_apps(html: &str) -> usize {
+        html.matches(r#"class="near-item mainnet""#).count()
+    }
+
+    #[test]
+    fn large_catalog_renders_full_pages_within_view_gas() {
+        let mut contract = new_contract();
+        let games = add_category(&mut contract, "games");
+        for index in 0..APPS {
+            let categories = if index % 2 == 0 { vec![games] } else { vec![] };
+            list_app(&mut contract, app_json(&format!("app{}", index), categories), &author());
+        }
+        // every third app is disabled, active ones must still fill the pages
+        for app_id in (0..APPS).step_by(3) {
+            set_predecessor(&guardian());
+            contract.disable_app(app_id);
+        }
+        let active_apps = APPS - APPS.div_ceil(3);
+        let active_games = (0..APPS).filter(|index| index % 2 == 0 && index % 3 != 0).count() as u64;
+        let total_pages = active_apps.div_ceil(DEFAULT_APPS_PER_PAGE);
+
+        let first_page = render(&contract, "/", 1);
+        assert_eq!(rendered_apps(&first_page), DEFAULT_APPS_PER_PAGE as usize);
+        assert!(first_page.contains(&format!("Page 1 of {}", total_pages)));
+
+        let last_page = render(&contract, "/", total_pages);
+        assert_eq!(rendered_apps(&last_page) as u64, active_apps - (total_pages - 1) * DEFAULT_APPS_PER_PAGE);
+        assert!(!last_page.contains("Oneliner of app0<"));
+
+        let category_page = render(&contract, "/category/games", 2);
+        assert_eq!(rendered_apps(&category_page), DEFAULT_APPS_PER_PAGE as usize);
+        assert!(category_page.contains(&format!("Page 2 of {}", active_games.div_ceil(DEFAULT_APPS_PER_PAGE))));
+    }
+
+    #[test]
+    fn menu_counts_apps_of_sub_categories_within_view_gas() {
+        let mut contract = new_contract();
+        let games = add_category(&mut contract, "games");
+        let arcade = add_category(&mut contract, "arcade");
+        let hidden = add_category(&mut contract, "hidden");
+        set_predecessor(&guardian());
+        contract.set_category_parent(arcade, Some(games));
+        contract.set_category_parent(hidden, Some(arcade));
+        contract.hide_category(hidden, true);
+        // many sibling categories used to make the menu quadratic
+        for index in 0..300 {
+            let category_id = add_category(&mut contract, &format!("tools{}", index));
+            set_predecessor(&guardian());
+            contract.set_category_parent(category_id, Some(games));
+        }
+        list_app(&mut contract, app_json("both", vec![games, arcade]), &author());
+        list_app(&mut contract, app_json("arcade", vec![arcade]), &author());
+        list_app(&mut contract, app_json("hidden", vec![hidden]), &author());
+
+        let html = render(&contract, "/", 1);
+        assert!(html.contains(r#"href="/category/games">Category games<span class="menu-badge">3</span>"#));
+        assert!(html.contains(r#"href="/category/arcade">Category arcade<span class="menu-badge">3</span>"#));
+        assert!(html.contains(r#"href="/category/tools0">Category tools0<span class="menu-badge">0</span>"#));
+        assert!(!html.contains(r#"href="/category/hidden""#));
+    }
+
+    #[test]
+    fn app_with_edit_slug_opens_its_page() {
+        assert_eq!(app_edit_slug("/app/dapp/edit"), Some("dapp"));
+        assert_eq!(app_edit_slug("/app/edit"), None);
+        assert_eq!(app_edit_slug("/app//edit"), None);
+
+        let mut contract = new_contract();
+        list_app(&mut contract, app_json("edit", vec![]), &author());
+        assert!(render(&contract, "/app/edit", 1).contains("Oneliner of edit"));
+        assert!(render(&contract, &app_edit_path("edit"), 1).contains(r#"action="/web4/contract/awesomeweb4.near/update_app_by_account_id""#));
+    }
+
+    #[test]
+    fn edit_form_keeps_hidden_categories() {
+        let mut contract = new_contract();
+        let games = add_category(&mut contract, "games");
+        let featured = add_category(&mut contract, "featured");
+        let archive = add_category(&mut contract, "archive");
+        set_predecessor(&guardian());
+        contract.hide_category(featured, true);
+        contract.hide_category(archive, true);
+        list_app(&mut contract, app_json("dapp", vec![games, featured]), &author());
+
+        let html = render(&contract, &app_edit_path("dapp"), 1);
+        assert!(html.contains(&format!(r#"<input type="checkbox" value="{}" name="app.categories[]" checked />"#, games)));
+        assert!(html.contains(&format!(r#"<input type="hidden" name="app.categories[]" value="{}">"#, featured)));
+        assert!(!html.contains(&format!(r#"value="{}" name="app.categories[]""#, archive)));
+        assert!(!html.contains(&format!(r#"name="app.categories[]" value="{}""#, archive)));
+    }
+
+    #[test]
+    fn admin_paginates_disabled_apps() {
+        let mut contract = new_contract();
+        let disabled_apps = DEFAULT_APPS_PER_PAGE + 5;
+        for app_id in 0..disabled_apps + 1 {
+            list_app(&mut contract, app_json(&format!("app{}", app_id), vec![]), &author());
+            if app_id < disabled_apps {
+                set_predecessor(&guardian());
+                contract.disable_app(app_id);
+            }
+        }
+        let enable_forms = |html: &str| html.matches("/enable_app_by_account_id").count() as u64;
+
+        let first_page = render_as(&contract, Some(guardian()), "/admin", 1);
+        assert_eq!(enable_forms(&first_page), DEFAULT_APPS_PER_PAGE);
+        assert!(first_page.contains("Page 1 of 2"));
+        assert_eq!(enable_forms(&render_as(&contract, Some(guardian()), "/admin", 2)), 5);
+    }
+
+    #[test]
+    fn app_page_shows_verified_owner() {
+        let mut contract = new_contract();
+        let app_id = list_app(&mut contract, app_json("dapp", vec![]), &author());
+        assert!(!render(&contract, "/app/dapp", 1).contains("Verified owner"));
+
+        set_predecessor(&"dapp.near".parse().unwrap());
+        contract.verify_app_owner(app_id);
+        assert!(render(&contract, "/app/dapp", 1).contains("Verified owner"));
+    }
+
+    #[test]
+    fn admin_sections_follow_roles() {
+        let mut contract = new_contract();
+        let moderator: AccountId = "moderator.near".parse().unwrap();
+        let curator: AccountId = "curator.near".parse().unwrap();
+        contract.internal_grant_role(&moderator, Role::Moderator, &guardian());
+        contract.internal_grant_role(&curator, Role::Curator, &guardian());
+        add_category(&mut contract, "games");
+        list_app(&mut contract, app_json("dapp", vec![]), &author());
+        set_predecessor(&guardian());
+        contract.disable_app(0);
+
+        let guardian_page = render_as(&contract, Some(guardian()), "/admin", 1);
+        assert!(!guardian_page.contains(r#"style="display:none""#));
+        assert!(guardian_page.contains("/enable_app_by_account_id"));
+        assert!(guardian_page.contains("/remove_guardian"));
+
+        let moderator_page = render_as(&contract, Some(moderator), "/admin", 1);
+        assert!(moderator_page.contains("/enable_app_by_account_id"));
+        assert!(!moderator_page.contains("/remove_guardian"));
+        assert!(!moderator_page.contains(r#"<a href="/category/games">"#));
+        assert_eq!(moderator_page.matches(r#"style="display:none""#).count(), 3);
+
+        let curator_page = render_as(&contract, Some(curator), "/admin", 1);
+        assert!(curator_page.contains(r#"<a href="/category/games">"#));
+        assert!(!curator_page.contains("/enable_app_by_account_id"));
+        assert!(!curator_page.contains("/remove_guardian"));
+        assert_eq!(curator_page.matches(r#"style="display:none""#).count(), 3);
+
+        let stranger_page = render_as(&contract, Some(stranger()), "/admin", 1);
+        assert!(stranger_page.contains("Only guardians, moderators and curators have access to this page."));
+        assert!(!stranger_page.contains("/enable_app_by_account_id"));
+    }
+
+    #[test]
+    fn last_possible_page_renders_empty() {
+        let mut contract = new_contract();
+        for index in 0..DEFAULT_APPS_PER_PAGE + 1 {
+            list_app(&mut contract, app_json(&format!("app{}", index), vec![]), &author());
+        }
+        let html = render(&contract, "/", u64::MAX);
+        assert_eq!(rendered_apps(&html), 0);
+        assert!(html.contains(&format!("Page {} of 2", u64::MAX)));
+    }
+}
@@ -0,0 +1,124 @@
+// Upgrades a registry deployed with the previous release to the current build and checks that the data survives.
+// Run `./build_local.sh` first, then `npm run test:workspace`.
+use serde_json::{json, Value};
+use workspaces::prelude::*;
+use workspaces::{Account, Contract, Network, Worker};
+
+// wasm of the release that stored disabled_apps and no state_version
+const OLD_WASM_FILEPATH: &str = "./contract/tests/workspaces/res/awesomeweb4_v1.wasm";
+// wasm produced by build_local.sh
+const NEW_WASM_FILEPATH: &str = "./out/local.wasm";
+
+const TGAS: u64 = 1_000_000_000_000;
+
+async fn view<T: Network>(worker: &Worker<T>, contract: &Contract, method: &str, args: Value) -> anyhow::Result<Value> {
+    contract.view(worker, method, args.to_string().into_bytes()).await?.json()
+}
+
+async fn call<T: Network>(worker: &Worker<T>, account: &Account, contract: &Contract, method: &str, args: Value) -> anyhow::Result<()> {
+    let outcome = account
+        .call(worker, contract.id(), method)
+        .args_json(args)?
+        .gas(300 * TGAS)
+        .transact()
+        .await?;
+    anyhow::ensure!(outcome.is_success(), "{} failed: {:?}", method, outcome.failures());
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox().await?;
+    let old_wasm = std::fs::read(OLD_WASM_FILEPATH)?;
+    let new_wasm = std::fs::read(NEW_WASM_FILEPATH)?;
+
+    let guardian = worker.dev_create_account().await?;
+    let contract = worker.dev_deploy(&old_wasm).await?;
+    call(&worker, &guardian, &contract, "new", json!({ "guardian_id": guardian.id() })).await?;
+
+    // any contract with web4_get passes the listing check, so the old registry wasm serves as a dapp
+    let dapp = worker.dev_deploy(&old_wasm).await?;
+    call(&worker, &guardian, &dapp, "new", json!({ "guardian_id": guardian.id() })).await?;
+    let disabled_dapp = worker.dev_deploy(&old_wasm).await?;
+    call(&worker, &guardian, &disabled_dapp, "new", json!({ "guardian_id": guardian.id() })).await?;
+
+    // populate the old state
+    call(&worker, &guardian, &contract, "add_category", json!({ "title": "Games", "slug": "games" })).await?;
+    call(&worker, &guardian, &contract, "add_category", json!({ "title": "Tools", "slug": "tools" })).await?;
+    call(&worker, &guardian, &contract, "add_app", json!({ "app": {
+        "title": "Chess Arena",
+        "dapp_account_id": dapp.id(),
+        "categories": ["0", "1"],
+        "slug": "chess",
+        "oneliner": "Onchain chess tournaments",
+        "description": "Play chess against other NEAR accounts",
+        "contracts": [dapp.id()],
+    }})).await?;
+    call(&worker, &guardian, &contract, "add_app", json!({ "app": {
+        "title": "Old Tool",
+        "dapp_account_id": disabled_dapp.id(),
+        "categories": ["1"],
+        "slug": "old-tool",
+    }})).await?;
+    call(&worker, &guardian, &contract, "disable_app", json!({ "app_id": 1 })).await?;
+
+    let apps_before = view(&worker, &contract, "get_apps", json!({})).await?;
+    let categories_before = view(&worker, &contract, "get_categories", json!({})).await?;
+    assert_eq!(apps_before.as_array().unwrap().len(), 2);
+
+    // upgrade
+    contract.as_account().deploy(&worker, &new_wasm).await?.into_result()?;
+    let stranger = worker.dev_create_account().await?;
+    anyhow::ensure!(call(&worker, &stranger, &contract, "migrate", json!({})).await.is_err(), "migrate must be restricted");
+    call(&worker, &guardian, &contract, "migrate", json!({})).await?;
+    // the catalog is complete before the search and author indexes are rebuilt
+    assert_eq!(view(&worker, &contract, "get_active_apps_count", json!({})).await?, json!(1));
+    call(&worker, &guardian, &contract, "rebuild_app_indexes", json!({})).await?;
+
+    assert_eq!(view(&worker, &contract, "get_state_version", json!({})).await?, json!(2));
+
+    // primary data
+    let apps_after = view(&worker, &contract, "get_apps", json!({})).await?;
+    for (before, after) in apps_before.as_array().unwrap().iter().zip(apps_after.as_array().unwrap()) {
+        assert_eq!(before[0], after[0]);
+        for field in ["title", "dapp_account_id", "categories", "slug", "oneliner", "description", "contracts", "active", "added_by_account_id"] {
+            assert_eq!(before[1][field], after[1][field], "app field {} changed", field);
+        }
+    }
+    let categories_after = view(&worker, &contract, "get_categories", json!({})).await?;
+    for (before, after) in categories_before.as_array().unwrap().iter().zip(categories_after.as_array().unwrap()) {
+        assert_eq!(before[0], after[0]);
+        assert_eq!(before[1]["title"], after[1]["title"]);
+        assert_eq!(before[1]["slug"], after[1]["slug"]);
+    }
+
+    // indexes carried over from the old layout
+    assert_eq!(view(&worker, &contract, "get_app_by_slug", json!({ "slug": "chess" })).await?["title"], "Chess Arena");
+    assert_eq!(view(&worker, &contract, "get_app_by_account_id", json!({ "account_id": dapp.id() })).await?["slug"], "chess");
+    assert_eq!(view(&worker, &contract, "get_category_apps_count", json!({ "category_id": 0 })).await?, json!(1));
+    assert_eq!(view(&worker, &contract, "get_category_apps_count", json!({ "category_id": 1 })).await?, json!(1));
+    assert_eq!(view(&worker, &contract, "get_active_apps_count", json!({})).await?, json!(1));
+
+    // indexes built by the migration and rebuild_app_indexes
+    assert_eq!(view(&worker, &contract, "get_category_by_slug", json!({ "slug": "tools" })).await?[0], json!(1));
+    let found = view(&worker, &contract, "search_apps", json!({ "query": "chess" })).await?;
+    assert_eq!(found.as_array().unwrap().len(), 1);
+    assert_eq!(found[0][0], json!(0));
+    let disabled_found = view(&worker, &contract, "search_apps", json!({ "query": "tool" })).await?;
+    assert!(disabled_found.as_array().unwrap().is_empty());
+    let by_author = view(&worker, &contract, "get_apps_by_author", json!({ "account_id": guardian.id() })).await?;
+    assert_eq!(by_author.as_array().unwrap().len(), 2);
+    assert_eq!(view(&worker, &contract, "get_guardians", json!({})).await?, json!([guardian.id()]));
+    assert_eq!(view(&worker, &contract, "get_roles", json!({ "account_id": guardian.id() })).await?, json!(["Owner"]));
+    assert_eq!(view(&worker, &contract, "get_app_contracts", json!({ "app_id": 0 })).await?,
+               json!([{ "account_id": dapp.id(), "verified": false }]));
+    // metadata is discovered only when an app is listed, apps listed before that have none
+    assert_eq!(view(&worker, &contract, "get_app_web4_metadata", json!({ "app_id": 0 })).await?, Value::Null);
+
+    // migrating the current layout again keeps the state
+    call(&worker, &guardian, &contract, "migrate", json!({})).await?;
+    assert_eq!(view(&worker, &contract, "get_apps", json!({})).await?, apps_after);
+
+    println!("Migration test passed");
+    Ok(())
+}